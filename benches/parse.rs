@@ -0,0 +1,20 @@
+//! A bench target without the default benchmark harness (`harness = false`), whose benchmarks
+//! are timed by `parameterized::bench`.
+
+use parameterized::parameterized_bench;
+
+#[parameterized_bench(input = { "1", "65535", "  42" })]
+fn parse(input: &str) -> Option<u16> {
+    input.trim().parse().ok()
+}
+
+#[parameterized_bench(length = { 16, 1024 })]
+fn sort(length: usize) -> Vec<u64> {
+    let mut values = (0..length as u64).rev().collect::<Vec<_>>();
+    values.sort_unstable();
+    values
+}
+
+fn main() {
+    parameterized::bench::main(&[parse::BENCHES, sort::BENCHES]);
+}
@@ -1,40 +1,335 @@
+use proc_macro2::Span;
 use quote::quote;
 use std::fmt::Formatter;
 use syn::parse::{Parse, ParseStream, Result};
 use syn::punctuated::Punctuated;
 use syn::token::{Async, Const, Unsafe};
-use syn::{braced, Attribute, Block, ItemFn, Meta, ReturnType, Visibility};
+use syn::{braced, Attribute, ItemFn, ItemImpl, Meta, ReturnType, Visibility};
 
-/// An ordered list of attribute arguments, which consists of (id, param-args) pairs.
+mod kw {
+    syn::custom_keyword!(exclude);
+    syn::custom_keyword!(sample);
+    syn::custom_keyword!(seed);
+    syn::custom_keyword!(env);
+    syn::custom_keyword!(fixture);
+    syn::custom_keyword!(flat);
+    syn::custom_keyword!(module);
+    syn::custom_keyword!(harness);
+    syn::custom_keyword!(aggregate);
+    syn::custom_keyword!(const_eval);
+    syn::custom_keyword!(returns);
+    syn::custom_keyword!(expect);
+    syn::custom_keyword!(oracle);
+    syn::custom_keyword!(snapshot);
+    syn::custom_keyword!(compile_fail);
+    syn::custom_keyword!(runtime);
+    syn::custom_keyword!(test_macro);
+    syn::custom_keyword!(tokio);
+    syn::custom_keyword!(async_std);
+    syn::custom_keyword!(smol);
+    syn::custom_keyword!(debug);
+    syn::custom_keyword!(display);
+}
+
+/// An ordered list of attribute arguments, which consists of (id, param-args) pairs, and the
+/// options which may be given alongside them.
 #[derive(Clone)]
 pub struct ParameterizedList {
-    pub args: Punctuated<ParameterList, Token![,]>,
+    pub args: Vec<ParameterList>,
+    pub constraint: Option<Constraint>,
+    pub exclusions: Option<Exclusions>,
+    pub sample: Option<Sample>,
+    pub seed: Option<Seed>,
+    pub fixture: Option<FixtureConstructor>,
+    /// Generate the test cases as siblings of the test function, instead of in a module
+    pub flat: Option<kw::flat>,
+    pub module: Option<ModuleName>,
+    pub runtime: Option<Runtime>,
+    pub test_macro: Option<TestMacro>,
+    /// Register the test cases with the built-in test runner, instead of marking them `#[test]`
+    pub harness: Option<kw::harness>,
+    /// Run all test cases within a single test, which reports the failing cases together
+    pub aggregate: Option<kw::aggregate>,
+    /// Evaluate each test case of a `const fn` at compile time, instead of generating a test
+    pub const_eval: Option<kw::const_eval>,
+    pub returns: Option<Returns>,
+    pub expect: Option<Expect>,
+    pub oracle: Option<Oracle>,
+    pub snapshot: Option<Snapshot>,
+    /// Check that the source returned by each test case fails to compile
+    pub compile_fail: Option<kw::compile_fail>,
+    /// Generate a benchmark for each test case; set by `#[parameterized_bench]`, not parsed
+    pub bench: bool,
+    /// The keys of the options which were given, which may not also be used as parameter names
+    pub reserved_keys: Vec<syn::Ident>,
 }
 
 impl Parse for ParameterizedList {
     /// This part parses
-    /// It uses IdentifiedArgList.parse() for each inner argument.
+    /// It uses Entry.parse() for each inner argument.
     ///
-    /// ['IdentifiedArgList.parse ']: struct.IdentifiedArgList
+    /// ['Entry.parse ']: enum.Entry
     fn parse(input: ParseStream) -> Result<Self> {
-        Ok(ParameterizedList {
-            args: Punctuated::parse_terminated(input)?,
-        })
+        let entries = Punctuated::<Entry, Token![,]>::parse_terminated(input)?;
+
+        let mut list = ParameterizedList {
+            args: Vec::new(),
+            constraint: None,
+            exclusions: None,
+            sample: None,
+            seed: None,
+            fixture: None,
+            flat: None,
+            module: None,
+            runtime: None,
+            test_macro: None,
+            harness: None,
+            aggregate: None,
+            const_eval: None,
+            returns: None,
+            expect: None,
+            oracle: None,
+            snapshot: None,
+            compile_fail: None,
+            bench: false,
+            reserved_keys: Vec::new(),
+        };
+
+        for entry in entries {
+            match entry {
+                Entry::Parameter(parameter) => {
+                    if RESERVED_KEYS.iter().any(|key| parameter.id == key) {
+                        return Err(reserved_key(&parameter.id));
+                    }
+                    list.args.push(parameter)
+                }
+                Entry::Constraint(constraint) => {
+                    let span = constraint.where_token.span;
+                    set_once(&mut list.constraint, constraint, "where", span)?
+                }
+                Entry::Exclusions(exclusions) => {
+                    let span = exclusions.exclude_token.span;
+                    list.reserved_keys.push(syn::Ident::new("exclude", span));
+                    set_once(&mut list.exclusions, exclusions, "exclude", span)?
+                }
+                Entry::Sample(sample) => {
+                    let span = sample.sample_token.span;
+                    list.reserved_keys.push(syn::Ident::new("sample", span));
+                    set_once(&mut list.sample, sample, "sample", span)?
+                }
+                Entry::Seed(seed) => {
+                    let span = seed.seed_token.span;
+                    list.reserved_keys.push(syn::Ident::new("seed", span));
+                    set_once(&mut list.seed, seed, "seed", span)?
+                }
+                Entry::Fixture(fixture) => {
+                    let span = fixture.fixture_token.span;
+                    list.reserved_keys.push(syn::Ident::new("fixture", span));
+                    set_once(&mut list.fixture, fixture, "fixture", span)?
+                }
+                Entry::Flat(flat) => set_once(&mut list.flat, flat, "flat", flat.span)?,
+                Entry::Harness(harness) => {
+                    set_once(&mut list.harness, harness, "harness", harness.span)?
+                }
+                Entry::Aggregate(aggregate) => {
+                    set_once(&mut list.aggregate, aggregate, "aggregate", aggregate.span)?
+                }
+                Entry::ConstEval(const_eval) => set_once(
+                    &mut list.const_eval,
+                    const_eval,
+                    "const_eval",
+                    const_eval.span,
+                )?,
+                Entry::Returns(returns) => {
+                    let span = returns.returns_token.span;
+                    list.reserved_keys.push(syn::Ident::new("returns", span));
+                    set_once(&mut list.returns, returns, "returns", span)?
+                }
+                Entry::Expect(expect) => {
+                    let span = expect.expect_token.span;
+                    list.reserved_keys.push(syn::Ident::new("expect", span));
+                    set_once(&mut list.expect, expect, "expect", span)?
+                }
+                Entry::Oracle(oracle) => {
+                    let span = oracle.oracle_token.span;
+                    list.reserved_keys.push(syn::Ident::new("oracle", span));
+                    set_once(&mut list.oracle, oracle, "oracle", span)?
+                }
+                Entry::Snapshot(snapshot) => {
+                    let span = snapshot.snapshot_token.span;
+                    set_once(&mut list.snapshot, snapshot, "snapshot", span)?
+                }
+                Entry::CompileFail(compile_fail) => set_once(
+                    &mut list.compile_fail,
+                    compile_fail,
+                    "compile_fail",
+                    compile_fail.span,
+                )?,
+                Entry::Runtime(runtime) => {
+                    let span = runtime.runtime_token.span;
+                    list.reserved_keys.push(syn::Ident::new("runtime", span));
+                    set_once(&mut list.runtime, runtime, "runtime", span)?
+                }
+                Entry::TestMacro(test_macro) => {
+                    let span = test_macro.test_macro_token.span;
+                    list.reserved_keys.push(syn::Ident::new("test_macro", span));
+                    set_once(&mut list.test_macro, test_macro, "test_macro", span)?
+                }
+                Entry::Module(module) => {
+                    let span = module.module_token.span;
+                    list.reserved_keys.push(syn::Ident::new("module", span));
+                    set_once(&mut list.module, module, "module", span)?
+                }
+            }
+        }
+
+        if let (Some(seed), None) = (&list.seed, &list.sample) {
+            return Err(syn::Error::new(
+                seed.seed_token.span,
+                "parameterized-macro: error: A `seed` can only be given together with `sample = <amount of cases>`",
+            ));
+        }
+
+        Ok(list)
+    }
+}
+
+/// The keys of options whose value isn't a list. A list given for such a key, e.g.
+/// `seed = { 1, 2 }`, is parsed as the arguments of a parameter instead, whose name is reserved.
+const RESERVED_KEYS: &[&str] = &[
+    "sample",
+    "seed",
+    "fixture",
+    "module",
+    "runtime",
+    "test_macro",
+    "oracle",
+    "snapshot",
+];
+
+/// The error for a list given to a reserved key, which would otherwise provide the values of a
+/// parameter with the same name.
+pub fn reserved_key(key: &syn::Ident) -> syn::Error {
+    syn::Error::new(
+        key.span(),
+        format!(
+            "parameterized-macro: error: `{0}` is a reserved key, and can't be used to provide values for the parameter named `{0}`; consider renaming the parameter",
+            key
+        ),
+    )
+}
+
+/// Whether the entry assigns a list, e.g. `key = { .. }`.
+fn is_list(input: ParseStream) -> bool {
+    input.peek2(Token![=]) && input.peek3(syn::token::Brace)
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, key: &str, span: Span) -> Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "parameterized-macro: error: found duplicate `{}` option",
+                key
+            ),
+        ));
+    }
+
+    *slot = Some(value);
+    Ok(())
+}
+
+/// A single entry of the `#[parameterized(..)]` attribute, which is either a parameter list
+/// or an option.
+enum Entry {
+    Parameter(ParameterList),
+    Constraint(Constraint),
+    Exclusions(Exclusions),
+    Sample(Sample),
+    Seed(Seed),
+    Fixture(FixtureConstructor),
+    Flat(kw::flat),
+    Module(ModuleName),
+    Runtime(Runtime),
+    TestMacro(TestMacro),
+    Harness(kw::harness),
+    Aggregate(kw::aggregate),
+    ConstEval(kw::const_eval),
+    Returns(Returns),
+    Expect(Expect),
+    Oracle(Oracle),
+    Snapshot(Snapshot),
+    CompileFail(kw::compile_fail),
+}
+
+impl Parse for Entry {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![where]) {
+            input.parse().map(Entry::Constraint)
+        } else if input.peek(kw::exclude) && input.peek2(Token![=]) {
+            input.parse().map(Entry::Exclusions)
+        } else if input.peek(kw::sample) && input.peek2(Token![=]) && !is_list(input) {
+            input.parse().map(Entry::Sample)
+        } else if input.peek(kw::seed) && input.peek2(Token![=]) && !is_list(input) {
+            input.parse().map(Entry::Seed)
+        } else if input.peek(kw::fixture) && input.peek2(Token![=]) && !is_list(input) {
+            input.parse().map(Entry::Fixture)
+        } else if input.peek(kw::flat) && !input.peek2(Token![=]) {
+            input.parse().map(Entry::Flat)
+        } else if input.peek(kw::harness) && !input.peek2(Token![=]) {
+            input.parse().map(Entry::Harness)
+        } else if input.peek(kw::aggregate) && !input.peek2(Token![=]) {
+            input.parse().map(Entry::Aggregate)
+        } else if input.peek(kw::const_eval) && !input.peek2(Token![=]) {
+            input.parse().map(Entry::ConstEval)
+        } else if input.peek(kw::module) && input.peek2(Token![=]) && !is_list(input) {
+            input.parse().map(Entry::Module)
+        } else if input.peek(kw::runtime) && input.peek2(Token![=]) && !is_list(input) {
+            input.parse().map(Entry::Runtime)
+        } else if input.peek(kw::test_macro) && input.peek2(Token![=]) && !is_list(input) {
+            input.parse().map(Entry::TestMacro)
+        } else if input.peek(kw::returns) && input.peek2(Token![=]) {
+            input.parse().map(Entry::Returns)
+        } else if input.peek(kw::expect) && input.peek2(Token![=]) {
+            input.parse().map(Entry::Expect)
+        } else if input.peek(kw::oracle) && input.peek2(Token![=]) && !is_list(input) {
+            input.parse().map(Entry::Oracle)
+        } else if input.peek(kw::snapshot) && !is_list(input) {
+            input.parse().map(Entry::Snapshot)
+        } else if input.peek(kw::compile_fail) && !input.peek2(Token![=]) {
+            input.parse().map(Entry::CompileFail)
+        } else {
+            input.parse().map(Entry::Parameter)
+        }
     }
 }
 
 /// A single (id, param-args) pair which consists of:
 ///   - id: identifier for the list
+///   - kind: whether the list contains values, types or const generic arguments
 ///   - param_args: ordered list arguments formatted using curly-braced list syntax, i.e. "{ 3, 4, 5 }"
+///   - type_args: ordered list of types, for lists of the form "type { u8, u16 }"
 ///
 /// For example:
-/// `parameter_name = { 3, 4, 5}`
+/// `parameter_name = { 3, 4, 5}`, `T = type { u8, u16 }` or `N = const { 1, 4 }`
 #[derive(Clone)]
 pub struct ParameterList {
     pub id: syn::Ident,
     _assignment: Token![=],
+    pub kind: ParameterKind,
     _braces: syn::token::Brace,
     pub param_args: Punctuated<syn::Expr, Token![,]>,
+    pub type_args: Punctuated<syn::Type, Token![,]>,
+}
+
+#[derive(Clone)]
+pub enum ParameterKind {
+    /// Values for a parameter of the test function
+    Value,
+    /// Types for a generic type parameter of the test function
+    Type,
+    /// Values for a const generic parameter of the test function
+    Const,
 }
 
 impl std::fmt::Debug for ParameterList {
@@ -46,46 +341,562 @@ impl std::fmt::Debug for ParameterList {
 impl Parse for ParameterList {
     // parts:
     //
-    // v = { a, b, c }
-    // $ident $Token![=] ${ $expr, ... }
+    // v = { a, b, c } | T = type { a, b } | N = const { a, b }
+    // $ident $Token![=] ($Token![type] | $Token![const])? ${ $expr, ... }
     fn parse(input: ParseStream) -> Result<Self> {
         let content;
 
+        let id = input.parse()?;
+        let _assignment = input.parse()?;
+
+        let kind = if input.parse::<Option<Token![type]>>()?.is_some() {
+            ParameterKind::Type
+        } else if input.parse::<Option<Token![const]>>()?.is_some() {
+            ParameterKind::Const
+        } else {
+            ParameterKind::Value
+        };
+
+        let _braces = braced!(content in input);
+
+        let (param_args, type_args) = match kind {
+            ParameterKind::Type => (Punctuated::new(), Punctuated::parse_terminated(&content)?),
+            ParameterKind::Value | ParameterKind::Const => {
+                (Punctuated::parse_terminated(&content)?, Punctuated::new())
+            }
+        };
+
         Ok(ParameterList {
-            id: input.parse()?,
+            id,
+            _assignment,
+            kind,
+            _braces,
+            param_args,
+            type_args,
+        })
+    }
+}
+
+/// A constraint which each test case must satisfy, written as a closure over the parameters
+/// of the test function.
+///
+/// For example:
+/// `where = |mode, threads| !(mode == Mode::Fast && threads == 0)`
+#[derive(Clone)]
+pub struct Constraint {
+    pub where_token: Token![where],
+    _assignment: Token![=],
+    pub closure: syn::ExprClosure,
+}
+
+impl Parse for Constraint {
+    // parts:
+    //
+    // where = |a, b| expr
+    // $Token![where] $Token![=] $closure
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Constraint {
+            where_token: input.parse()?,
+            _assignment: input.parse()?,
+            closure: input.parse()?,
+        })
+    }
+}
+
+/// A list of test cases to exclude. Each row is a tuple with one element per parameter list,
+/// in the order in which the parameter lists were given. An element may be `_` to match any value.
+///
+/// For example:
+/// `exclude = { (Mode::Fast, 0), (Mode::Slow, _) }`
+#[derive(Clone)]
+pub struct Exclusions {
+    pub exclude_token: kw::exclude,
+    _assignment: Token![=],
+    _braces: syn::token::Brace,
+    pub rows: Punctuated<syn::Expr, Token![,]>,
+}
+
+impl Parse for Exclusions {
+    // parts:
+    //
+    // exclude = { (a, b), (c, _) }
+    // exclude $Token![=] ${ $expr, ... }
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+
+        Ok(Exclusions {
+            exclude_token: input.parse()?,
+            _assignment: input.parse()?,
+            _braces: braced!(content in input),
+            rows: Punctuated::parse_terminated(&content)?,
+        })
+    }
+}
+
+/// The amount of test cases to randomly select from all test cases.
+///
+/// For example:
+/// `sample = 200`
+#[derive(Clone)]
+pub struct Sample {
+    pub sample_token: kw::sample,
+    _assignment: Token![=],
+    pub amount: syn::LitInt,
+}
+
+impl Parse for Sample {
+    // parts:
+    //
+    // sample = 200
+    // sample $Token![=] $lit
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Sample {
+            sample_token: input.parse()?,
+            _assignment: input.parse()?,
+            amount: input.parse()?,
+        })
+    }
+}
+
+/// The seed used to select a `sample` of the test cases, either given directly, or read from an
+/// environment variable when the macro is expanded.
+///
+/// For example:
+/// `seed = 42` or `seed = env("PARAMETERIZED_SEED")`
+#[derive(Clone)]
+pub struct Seed {
+    pub seed_token: kw::seed,
+    _assignment: Token![=],
+    pub source: SeedSource,
+}
+
+#[derive(Clone)]
+pub enum SeedSource {
+    Literal(syn::LitInt),
+    Env(syn::LitStr),
+}
+
+impl Parse for Seed {
+    // parts:
+    //
+    // seed = 42 | seed = env("NAME")
+    // seed $Token![=] ($lit | env($lit))
+    fn parse(input: ParseStream) -> Result<Self> {
+        let seed_token = input.parse()?;
+        let _assignment = input.parse()?;
+
+        let source = if input.peek(kw::env) {
+            let content;
+            input.parse::<kw::env>()?;
+            parenthesized!(content in input);
+            SeedSource::Env(content.parse()?)
+        } else {
+            SeedSource::Literal(input.parse()?)
+        };
+
+        Ok(Seed {
+            seed_token,
+            _assignment,
+            source,
+        })
+    }
+}
+
+/// The constructor of the fixture on which a parameterized method is called. If not given,
+/// the fixture is constructed with `Default::default()`.
+///
+/// For example:
+/// `fixture = TestCtx::new`
+#[derive(Clone)]
+pub struct FixtureConstructor {
+    pub fixture_token: kw::fixture,
+    _assignment: Token![=],
+    pub constructor: syn::Path,
+}
+
+impl Parse for FixtureConstructor {
+    // parts:
+    //
+    // fixture = path::to::constructor
+    // fixture $Token![=] $path
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(FixtureConstructor {
+            fixture_token: input.parse()?,
+            _assignment: input.parse()?,
+            constructor: input.parse()?,
+        })
+    }
+}
+
+/// The values which the test function is expected to return, one per test case.
+///
+/// For example:
+/// `returns = { 5, 6, 7 }`
+#[derive(Clone)]
+pub struct Returns {
+    pub returns_token: kw::returns,
+    _assignment: Token![=],
+    _braces: syn::token::Brace,
+    pub values: Punctuated<syn::Expr, Token![,]>,
+}
+
+impl Parse for Returns {
+    // parts:
+    //
+    // returns = { a, b, c }
+    // returns $Token![=] ${ $expr, ... }
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+
+        Ok(Returns {
+            returns_token: input.parse()?,
+            _assignment: input.parse()?,
+            _braces: braced!(content in input),
+            values: Punctuated::parse_terminated(&content)?,
+        })
+    }
+}
+
+/// The patterns which the values returned by the test function are expected to match, one per
+/// test case. Unlike the arguments of a parameter, these are parsed as patterns.
+///
+/// For example:
+/// `expect = { Ok(_), Err(ParseError::Empty) }`
+#[derive(Clone)]
+pub struct Expect {
+    pub expect_token: kw::expect,
+    _assignment: Token![=],
+    _braces: syn::token::Brace,
+    pub patterns: Punctuated<syn::Pat, Token![,]>,
+}
+
+impl Parse for Expect {
+    // parts:
+    //
+    // expect = { Ok(_), Err(_) | None }
+    // expect $Token![=] ${ $pat, ... }
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+
+        Ok(Expect {
+            expect_token: input.parse()?,
             _assignment: input.parse()?,
             _braces: braced!(content in input),
-            param_args: Punctuated::parse_terminated(&content)?,
+            patterns: Punctuated::parse_terminated_with(
+                &content,
+                syn::Pat::parse_multi_with_leading_vert,
+            )?,
+        })
+    }
+}
+
+/// A reference implementation, which is called with the same arguments as the test function, and
+/// whose result the value returned by the test function should equal.
+///
+/// For example:
+/// `oracle = reference::parse`
+#[derive(Clone)]
+pub struct Oracle {
+    pub oracle_token: kw::oracle,
+    _assignment: Token![=],
+    pub path: syn::Path,
+}
+
+impl Parse for Oracle {
+    // parts:
+    //
+    // oracle = path::to::function
+    // oracle $Token![=] $path
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Oracle {
+            oracle_token: input.parse()?,
+            _assignment: input.parse()?,
+            path: input.parse()?,
+        })
+    }
+}
+
+/// Compare the value returned by each test case with a snapshot file, using its `Debug`
+/// representation by default, or its `Display` representation.
+///
+/// For example:
+/// `snapshot` or `snapshot = display`
+#[derive(Clone)]
+pub struct Snapshot {
+    pub snapshot_token: kw::snapshot,
+    pub format: SnapshotFormat,
+}
+
+#[derive(Clone, Copy)]
+pub enum SnapshotFormat {
+    Debug,
+    Display,
+}
+
+impl Parse for Snapshot {
+    // parts:
+    //
+    // snapshot | snapshot = debug | snapshot = display
+    // snapshot ($Token![=] (debug | display))?
+    fn parse(input: ParseStream) -> Result<Self> {
+        let snapshot_token = input.parse()?;
+
+        let explicit = input.parse::<Option<Token![=]>>()?.is_some();
+
+        let format = if !explicit || input.parse::<Option<kw::debug>>()?.is_some() {
+            SnapshotFormat::Debug
+        } else if input.parse::<Option<kw::display>>()?.is_some() {
+            SnapshotFormat::Display
+        } else {
+            return Err(input.error(
+                "parameterized-macro: error: Expected the format of the snapshots, either `debug` or `display`",
+            ));
+        };
+
+        Ok(Snapshot {
+            snapshot_token,
+            format,
+        })
+    }
+}
+
+/// The name of the module which contains the generated test cases, instead of the name of the
+/// test function.
+///
+/// For example:
+/// `module = add5_tests`
+#[derive(Clone)]
+pub struct ModuleName {
+    pub module_token: kw::module,
+    _assignment: Token![=],
+    pub name: syn::Ident,
+}
+
+impl Parse for ModuleName {
+    // parts:
+    //
+    // module = name
+    // module $Token![=] $ident
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(ModuleName {
+            module_token: input.parse()?,
+            _assignment: input.parse()?,
+            name: input.parse()?,
+        })
+    }
+}
+
+/// The test macros of the test cases, instead of `#[test]`; the same as a
+/// `#[parameterized_macro(..)]` attribute, without depending on the order of the attributes.
+///
+/// For example:
+/// `test_macro = tokio::test(flavor = "multi_thread")` or
+/// `test_macro = [tokio::test, serial_test::serial]`
+#[derive(Clone)]
+pub struct TestMacro {
+    pub test_macro_token: kw::test_macro,
+    _assignment: Token![=],
+    pub test_macros: Punctuated<Meta, Token![,]>,
+}
+
+impl Parse for TestMacro {
+    // parts:
+    //
+    // test_macro = path::to::test | test_macro = [path::to::test, ..]
+    // test_macro $Token![=] ( $meta | [ $meta,* ] )
+    fn parse(input: ParseStream) -> Result<Self> {
+        let test_macro_token: kw::test_macro = input.parse()?;
+        let _assignment = input.parse()?;
+
+        let test_macros = if input.peek(syn::token::Bracket) {
+            let content;
+            bracketed!(content in input);
+            Punctuated::parse_terminated(&content)?
+        } else {
+            let mut test_macros = Punctuated::new();
+            test_macros.push(input.parse()?);
+            test_macros
+        };
+
+        if test_macros.is_empty() {
+            return Err(syn::Error::new(
+                test_macro_token.span,
+                "parameterized-macro: error: Expected at least one test macro, e.g. `test_macro = tokio::test`",
+            ));
+        }
+
+        Ok(TestMacro {
+            test_macro_token,
+            _assignment,
+            test_macros,
         })
     }
 }
 
-// TODO: add to parse, code gen of ParameterizedList
+/// The async runtime which runs the test cases of an `async` test function, instead of a test
+/// macro given with `#[parameterized_macro(..)]`.
+///
+/// For example:
+/// `runtime = tokio`, `runtime = tokio(flavor = "multi_thread", worker_threads = 2)`,
+/// `runtime = async_std` or `runtime = smol`
+#[derive(Clone)]
+pub struct Runtime {
+    pub runtime_token: kw::runtime,
+    _assignment: Token![=],
+    pub kind: RuntimeKind,
+    /// The options of the `tokio` runtime, i.e. `flavor` and `worker_threads`
+    pub options: Punctuated<syn::MetaNameValue, Token![,]>,
+}
+
+#[derive(Clone, Copy)]
+pub enum RuntimeKind {
+    Tokio,
+    AsyncStd,
+    Smol,
+}
+
+impl Parse for Runtime {
+    // parts:
+    //
+    // runtime = tokio | runtime = tokio(flavor = "..", worker_threads = ..)
+    // runtime = async_std | runtime = smol
+    // runtime $Token![=] $runtime ( $paren ( $name $Token![=] $expr ),* )?
+    fn parse(input: ParseStream) -> Result<Self> {
+        let runtime_token = input.parse()?;
+        let _assignment = input.parse()?;
+
+        let lookahead = input.lookahead1();
+        let kind = if lookahead.peek(kw::tokio) {
+            input.parse::<kw::tokio>().map(|_| RuntimeKind::Tokio)?
+        } else if lookahead.peek(kw::async_std) {
+            input
+                .parse::<kw::async_std>()
+                .map(|_| RuntimeKind::AsyncStd)?
+        } else if lookahead.peek(kw::smol) {
+            input.parse::<kw::smol>().map(|_| RuntimeKind::Smol)?
+        } else {
+            return Err(syn::Error::new(
+                lookahead.error().span(),
+                "parameterized-macro: error: Expected the async runtime of the test cases, either `tokio`, `async_std` or `smol`",
+            ));
+        };
+
+        let options = if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            Punctuated::<syn::MetaNameValue, Token![,]>::parse_terminated(&content)?
+        } else {
+            Punctuated::new()
+        };
+
+        for option in &options {
+            let known = matches!(kind, RuntimeKind::Tokio)
+                && (option.path.is_ident("flavor") || option.path.is_ident("worker_threads"));
+
+            if !known {
+                return Err(syn::Error::new_spanned(
+                    &option.path,
+                    match kind {
+                        RuntimeKind::Tokio => "parameterized-macro: error: Unknown option of the `tokio` runtime, expected `flavor` or `worker_threads`",
+                        _ => "parameterized-macro: error: Only the `tokio` runtime takes options",
+                    },
+                ));
+            }
+        }
+
+        Ok(Runtime {
+            runtime_token,
+            _assignment,
+            kind,
+            options,
+        })
+    }
+}
+
+/// An attribute of the test function: either the test macros of its test cases, or an attribute
+/// which is retained on the generated code.
+// only the attributes of a single item are held per expansion, so their size doesn't matter
+#[allow(clippy::large_enum_variant)]
 pub enum MacroAttribute {
-    /// A `#[parameterized_macro(..)]` attribute
+    /// A `#[parameterized_macro(..)]` attribute, with one or more test macros which are applied
+    /// to each test case in the given order
     ///
-    /// Example usage: `#[parameterized_macro(tokio::test)]`
-    UseTestMacro(Meta),
+    /// Example usage: `#[parameterized_macro(tokio::test)]` or
+    /// `#[parameterized_macro(tokio::test, serial_test::serial)]`
+    UseTestMacro(Punctuated<Meta, Token![,]>),
     /// An attribute unrelated to this crate; to be retained after the generation step
     Unrelated(Attribute),
 }
 
 impl MacroAttribute {
+    pub fn from_attribute(attr: Attribute) -> Result<Self> {
+        if attr.path().is_ident("parameterized_macro") {
+            let test_macros = attr.parse_args_with(Punctuated::parse_terminated)?;
+
+            if test_macros.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "parameterized-macro: error: Expected at least one test macro, e.g. #[parameterized_macro(tokio::test)]",
+                ));
+            }
+
+            Ok(MacroAttribute::UseTestMacro(test_macros))
+        } else {
+            Ok(MacroAttribute::Unrelated(attr))
+        }
+    }
+
     pub fn is_use_test_macro(&self) -> bool {
         matches!(self, Self::UseTestMacro(_))
     }
 
     pub fn quoted(&self) -> proc_macro2::TokenStream {
         match self {
-            Self::UseTestMacro(meta) => quote!(#meta),
+            Self::UseTestMacro(test_macros) => {
+                let test_macros = test_macros.iter();
+                quote!(#(#[#test_macros])*)
+            }
             Self::Unrelated(attr) => quote!(#attr),
         }
     }
 }
 
+/// The item annotated with `#[parameterized(..)]`: either a test function, or an `impl` block
+/// which contains parameterized methods.
+// only a single item is parsed per expansion, so its size doesn't matter
+#[allow(clippy::large_enum_variant)]
+pub enum Item {
+    Fn(Fn),
+    Impl(ItemImpl),
+}
+
+impl Parse for Item {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let fork = input.fork();
+        fork.call(Attribute::parse_outer)?;
+        fork.parse::<Option<Token![unsafe]>>()?;
+
+        if fork.peek(Token![impl]) {
+            input.parse().map(Item::Impl)
+        } else {
+            input.parse().map(Item::Fn)
+        }
+    }
+}
+
 pub struct Fn {
     pub attrs: Vec<MacroAttribute>,
     pub item_fn: ItemFn,
+    /// Set if the test function is a method of a fixture
+    pub method: Option<Method>,
+}
+
+/// A parameterized method, which is called on a fixture constructed by each test case.
+pub struct Method {
+    pub self_ty: syn::Type,
+    /// `None` for associated functions, which don't need a fixture
+    pub receiver: Option<syn::Receiver>,
+    pub constructor: Option<syn::Path>,
 }
 
 impl Parse for Fn {
@@ -93,23 +904,44 @@ impl Parse for Fn {
         let attrs = input
             .call(Attribute::parse_outer)?
             .into_iter()
-            .map(|attr| {
-                if attr.path().is_ident("parameterized_macro") {
-                    attr.parse_args::<Meta>().map(MacroAttribute::UseTestMacro)
-                } else {
-                    Ok(MacroAttribute::Unrelated(attr))
-                }
-            })
+            .map(MacroAttribute::from_attribute)
             .collect::<Result<Vec<MacroAttribute>>>()?;
 
         Ok(Self {
             attrs,
             item_fn: input.parse()?,
+            method: None,
         })
     }
 }
 
 impl Fn {
+    /// Add the test macros given by the `test_macro` key of the attribute, which may not be
+    /// combined with a `#[parameterized_macro(..)]` attribute.
+    pub fn add_test_macro(&mut self, test_macro: Option<&TestMacro>) -> Result<()> {
+        let test_macro = match test_macro {
+            Some(test_macro) => test_macro,
+            None => return Ok(()),
+        };
+
+        if let Some(MacroAttribute::UseTestMacro(test_macros)) =
+            self.attrs.iter().find(|attr| attr.is_use_test_macro())
+        {
+            return Err(syn::Error::new_spanned(
+                test_macros,
+                "parameterized-macro: error: The test macros are already given by `test_macro`, \
+                    and can't be given by #[parameterized_macro(..)] as well",
+            ));
+        }
+
+        self.attrs.insert(
+            0,
+            MacroAttribute::UseTestMacro(test_macro.test_macros.clone()),
+        );
+
+        Ok(())
+    }
+
     pub fn constness(&self) -> Option<&Const> {
         self.item_fn.sig.constness.as_ref()
     }
@@ -129,8 +961,27 @@ impl Fn {
     pub fn return_type(&self) -> &ReturnType {
         &self.item_fn.sig.output
     }
+}
+
+#[cfg(test)]
+mod reserved_keys {
+    use super::*;
 
-    pub fn body(&self) -> &Block {
-        &self.item_fn.block
+    #[test]
+    fn list_given_to_reserved_key() {
+        for key in RESERVED_KEYS {
+            let attribute = format!("v = {{ 1, 2 }}, {} = {{ 1, 2 }}", key);
+            let error = syn::parse_str::<ParameterizedList>(&attribute)
+                .err()
+                .unwrap_or_else(|| panic!("a list given to `{}` was accepted", key));
+
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "parameterized-macro: error: `{0}` is a reserved key, and can't be used to provide values for the parameter named `{0}`; consider renaming the parameter",
+                    key
+                )
+            );
+        }
     }
 }
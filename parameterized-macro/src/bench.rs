@@ -0,0 +1,105 @@
+use proc_macro2::{Span, TokenStream};
+
+use crate::attribute::{Fn, ParameterizedList};
+use crate::diagnostics::{reject_options, reject_test_attributes, test_options};
+use crate::generation::call_arguments;
+use crate::tests::TestCases;
+
+/// Benchmarks generated by `#[parameterized_bench(..)]`, which are timed by the built-in
+/// benchmark runner of the `parameterized` crate (`parameterized::bench`).
+///
+/// Each test case becomes a function which takes a `Bencher`, and the generated module contains
+/// a `BENCHES` constant, which lists the benchmarks together with their arguments.
+pub struct Bench;
+
+impl Bench {
+    pub fn new(argument_lists: &ParameterizedList, f: &Fn) -> syn::Result<Option<Self>> {
+        if !argument_lists.bench {
+            return Ok(None);
+        }
+
+        let mut options = test_options(argument_lists);
+        options.extend(argument_lists.flat.map(|flat| ("flat", flat.span)));
+        reject_options(
+            &options,
+            "#[parameterized_bench]",
+            "which generates benchmarks instead of test cases",
+        )?;
+
+        if let Some(asyncness) = f.asyncness() {
+            return Err(syn::Error::new_spanned(
+                asyncness,
+                "parameterized-macro: error: An `async` function can't be benchmarked by #[parameterized_bench]",
+            ));
+        }
+
+        reject_test_attributes(
+            f,
+            "#[parameterized_bench]",
+            "since the benchmarks are run by the built-in benchmark runner",
+        )?;
+
+        Ok(Some(Self))
+    }
+
+    /// Generate the `BENCHES` constant, which registers the given benchmarks by their index and
+    /// the name of the generated function.
+    pub fn generate_registry(
+        &self,
+        argument_lists: &ParameterizedList,
+        values: &TestCases,
+        cases: &[(usize, syn::Ident)],
+    ) -> TokenStream {
+        let entries = cases.iter().map(|(i, identifier)| {
+            let name = format!("::{}", identifier);
+
+            let parameters = argument_lists.args.iter().map(|list| {
+                let parameter = list.id.to_string();
+                let argument = values.get_argument(&list.id, *i).display();
+                quote::quote!((#parameter, #argument))
+            });
+
+            quote::quote! {
+                ::parameterized::bench::Bench {
+                    index: #i,
+                    path: ::core::concat!(::core::module_path!(), #name),
+                    parameters: &[#(#parameters),*],
+                    run: #identifier,
+                }
+            }
+        });
+
+        quote::quote! {
+            /// The benchmarks, to be run by `parameterized::bench::main`.
+            pub const BENCHES: &[::parameterized::bench::Bench] = &[#(#entries),*];
+        }
+    }
+
+    /// Generate the body of a benchmark, which times the `invocation` of the benchmarked
+    /// function. The arguments are bound by the `bindings` for each iteration, outside of the
+    /// measured time.
+    pub fn generate_body(
+        &self,
+        f: &Fn,
+        bindings: &[TokenStream],
+        invocation: TokenStream,
+    ) -> TokenStream {
+        let arguments = call_arguments(f);
+        let bencher = bencher();
+
+        quote::quote! {
+            #bencher.iter_with_setup(
+                || {
+                    #(#bindings)*
+                    (#(#arguments,)*)
+                },
+                |(#(#arguments,)*)| #invocation,
+            )
+        }
+    }
+}
+
+/// The parameter of a benchmark, which times its iterations.
+pub fn bencher() -> syn::Ident {
+    syn::Ident::new("__parameterized_bencher", Span::call_site())
+}
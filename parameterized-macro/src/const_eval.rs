@@ -0,0 +1,96 @@
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+
+use crate::attribute::{Fn, ParameterizedList};
+use crate::diagnostics::{error, reject_options, reject_test_attributes, test_options};
+use crate::generation::returns_unit;
+
+/// Evaluation of the test cases by the compiler, for `const_eval`.
+///
+/// Each test case is emitted as an anonymous constant, `const _: () = { .. };`, which calls the
+/// `const fn` test function with its arguments. A failing assertion is then reported as a
+/// compile error, in every build of the crate, rather than only by `cargo test`.
+pub struct ConstEval;
+
+impl ConstEval {
+    pub fn new(argument_lists: &ParameterizedList, f: &Fn) -> syn::Result<Option<Self>> {
+        let span = match &argument_lists.const_eval {
+            Some(const_eval) => const_eval.span,
+            None => return Ok(None),
+        };
+
+        if argument_lists.bench {
+            return Err(error(
+                span,
+                "`const_eval` can't be used with #[parameterized_bench]",
+            ));
+        }
+
+        let reason = "since the test cases are evaluated by the compiler";
+        reject_options(&test_options(argument_lists), "`const_eval`", reason)?;
+
+        if f.constness().is_none() {
+            return Err(error(
+                span,
+                "`const_eval` can only be used with a `const fn` test function",
+            ));
+        }
+
+        if f.method.is_some() {
+            return Err(error(
+                span,
+                "`const_eval` can't be used with a parameterized method, since its fixture is constructed at runtime",
+            ));
+        }
+
+        reject_test_attributes(f, "`const_eval`", reason)?;
+
+        Ok(Some(Self))
+    }
+
+    /// Generate the constant which evaluates a test case, by its `bindings` and the
+    /// `invocation` of the test function.
+    ///
+    /// The invocation is spanned by the arguments of the test case, at `case_span`, so a failing
+    /// evaluation points at the test case in the attribute, rather than at the attribute as a
+    /// whole.
+    pub fn generate_case(
+        &self,
+        f: &Fn,
+        bindings: &[TokenStream],
+        invocation: TokenStream,
+        case_span: Span,
+    ) -> TokenStream {
+        let invocation = respan(invocation, case_span);
+        let evaluation = if returns_unit(f.return_type()) {
+            quote::quote!(#invocation;)
+        } else {
+            quote::quote!(let _ = #invocation;)
+        };
+
+        quote::quote! {
+            const _: () = {
+                #(#bindings)*
+
+                #evaluation
+            };
+        }
+    }
+}
+
+/// Set the span of each of the `tokens` to `span`.
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Group(group) => {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                TokenTree::Group(respanned)
+            }
+            mut token => {
+                token.set_span(span);
+                token
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,293 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use std::cmp::Ordering;
+
+use crate::attribute::ParameterizedList;
+use crate::generation::FnArgPair;
+use crate::tests::{Argument, TestCases};
+
+/// The outcome of checking a single test case against the constraints of a parameterized test.
+pub enum Verdict {
+    /// The case satisfies every constraint.
+    Keep,
+    /// The case violates a constraint, and will not be generated at all.
+    Drop,
+    /// Whether the case satisfies the constraints can only be decided at runtime. Contains the
+    /// condition under which the case must be skipped.
+    Runtime(TokenStream),
+}
+
+/// The `where = |..| ..` and `exclude = { .. }` options of a parameterized test, checked against
+/// the test function's parameters.
+pub struct Constraints<'ctx> {
+    condition: Option<Condition<'ctx>>,
+    exclusions: Vec<Vec<(&'ctx syn::Ident, &'ctx syn::Expr)>>,
+    parameters: Vec<&'ctx syn::Ident>,
+}
+
+struct Condition<'ctx> {
+    inputs: Vec<&'ctx syn::Ident>,
+    body: &'ctx syn::Expr,
+}
+
+impl<'ctx> Constraints<'ctx> {
+    pub fn new(list: &'ctx ParameterizedList, parameters: &[FnArgPair<'ctx>]) -> syn::Result<Self> {
+        let parameters = parameters.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+        let condition = list
+            .constraint
+            .as_ref()
+            .map(|constraint| -> syn::Result<Condition> {
+                let inputs = constraint
+                    .closure
+                    .inputs
+                    .iter()
+                    .map(|input| match input {
+                        syn::Pat::Ident(syn::PatIdent { ident, .. })
+                            if parameters.contains(&ident) =>
+                        {
+                            Ok(ident)
+                        }
+                        _ => Err(syn::Error::new_spanned(
+                            input,
+                            "parameterized-macro: error: The inputs of a `where` constraint should be parameters of the test function",
+                        )),
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+
+                Ok(Condition {
+                    inputs,
+                    body: &constraint.closure.body,
+                })
+            })
+            .transpose()?;
+
+        let exclusions = list
+            .exclusions
+            .iter()
+            .flat_map(|exclusions| {
+                exclusions.rows.iter().map(|row| {
+                    let elements = match row {
+                        syn::Expr::Tuple(tuple) if list.args.len() != 1 => {
+                            tuple.elems.iter().collect::<Vec<_>>()
+                        }
+                        expr => vec![expr],
+                    };
+
+                    if elements.len() != list.args.len() {
+                        return Err(syn::Error::new_spanned(
+                            row,
+                            format!(
+                                "parameterized-macro: error: Each excluded case should have one value per parameter list.\n\
+                                    Expected {} values, but got: {}",
+                                list.args.len(),
+                                elements.len(),
+                            ),
+                        ));
+                    }
+
+                    Ok(list.args.iter().map(|args| &args.id).zip(elements).collect())
+                })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            condition,
+            exclusions,
+            parameters,
+        })
+    }
+
+    /// Decide whether the `ith` test case should be generated.
+    pub fn check(&self, test_cases: &TestCases, ith: usize) -> Verdict {
+        let mut runtime_checks = Vec::new();
+
+        if let Some(condition) = &self.condition {
+            let scope = Scope {
+                bindings: condition
+                    .inputs
+                    .iter()
+                    .map(|id| (*id, test_cases.get(id, ith)))
+                    .collect(),
+                parameters: &self.parameters,
+            };
+
+            match as_bool(scope.evaluate(condition.body)) {
+                Some(true) => {}
+                Some(false) => return Verdict::Drop,
+                None => {
+                    let body = condition.body;
+                    runtime_checks.push(quote::quote!(!(#body)));
+                }
+            }
+        }
+
+        for row in &self.exclusions {
+            let mut undecided = Vec::new();
+            let mut matches = true;
+
+            for (id, value) in row {
+                match matches_value(test_cases.get_argument(id, ith), value) {
+                    Some(true) => {}
+                    Some(false) => matches = false,
+                    None => undecided.push(quote::quote!(#id == (#value))),
+                }
+            }
+
+            if !matches {
+                continue;
+            }
+
+            if undecided.is_empty() {
+                return Verdict::Drop;
+            }
+
+            runtime_checks.push(quote::quote!((#(#undecided)&&*)));
+        }
+
+        if runtime_checks.is_empty() {
+            Verdict::Keep
+        } else {
+            Verdict::Runtime(quote::quote!(#(#runtime_checks)||*))
+        }
+    }
+}
+
+/// Whether a case argument matches an element of an excluded row, if this can be decided at
+/// expansion time.
+///
+/// Types can't be compared at runtime, so they are compared as written instead.
+fn matches_value(argument: Argument, element: &syn::Expr) -> Option<bool> {
+    if let syn::Expr::Infer(_) = element {
+        return Some(true);
+    }
+
+    if argument.to_token_stream().to_string() == element.to_token_stream().to_string() {
+        return Some(true);
+    }
+
+    match argument {
+        Argument::Value(expr) | Argument::Const(expr) => {
+            let scope = Scope::empty();
+            equals(&scope.evaluate(expr)?, &scope.evaluate(element)?)
+        }
+        Argument::Type(_) => Some(false),
+    }
+}
+
+/// A value which could be computed at expansion time.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Bool(bool),
+    Int(i128),
+    Char(char),
+    Str(String),
+    /// An opaque path, such as an enum variant or a constant. Two paths are only known to be
+    /// equal if they are written identically.
+    Path(String),
+}
+
+fn as_bool(value: Option<Value>) -> Option<bool> {
+    match value {
+        Some(Value::Bool(b)) => Some(b),
+        _ => None,
+    }
+}
+
+fn equals(lhs: &Value, rhs: &Value) -> Option<bool> {
+    match (lhs, rhs) {
+        (Value::Path(l), Value::Path(r)) if l == r => Some(true),
+        (Value::Path(_), _) | (_, Value::Path(_)) => None,
+        (Value::Bool(_), Value::Bool(_))
+        | (Value::Int(_), Value::Int(_))
+        | (Value::Char(_), Value::Char(_))
+        | (Value::Str(_), Value::Str(_)) => Some(lhs == rhs),
+        _ => None,
+    }
+}
+
+fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Value::Int(l), Value::Int(r)) => Some(l.cmp(r)),
+        (Value::Char(l), Value::Char(r)) => Some(l.cmp(r)),
+        (Value::Str(l), Value::Str(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+/// The names which are in scope while evaluating a constraint at expansion time.
+struct Scope<'a> {
+    /// Closure inputs, bound to the argument expressions of the current case
+    bindings: Vec<(&'a syn::Ident, &'a syn::Expr)>,
+    /// Parameters of the test function; those which aren't bound can't be evaluated
+    parameters: &'a [&'a syn::Ident],
+}
+
+impl<'a> Scope<'a> {
+    fn empty() -> Self {
+        Self {
+            bindings: Vec::new(),
+            parameters: &[],
+        }
+    }
+
+    /// Evaluates a (small) subset of Rust expressions: literals, paths, and the logical and
+    /// comparison operators. Returns `None` if the expression can't be evaluated at expansion time.
+    fn evaluate(&self, expr: &syn::Expr) -> Option<Value> {
+        match expr {
+            syn::Expr::Lit(syn::ExprLit { lit, .. }) => match lit {
+                syn::Lit::Bool(b) => Some(Value::Bool(b.value)),
+                syn::Lit::Int(i) => i.base10_parse().ok().map(Value::Int),
+                syn::Lit::Char(c) => Some(Value::Char(c.value())),
+                syn::Lit::Str(s) => Some(Value::Str(s.value())),
+                _ => None,
+            },
+            syn::Expr::Path(syn::ExprPath {
+                qself: None, path, ..
+            }) => match path.get_ident() {
+                Some(id) => match self.bindings.iter().find(|(bound, _)| *bound == id) {
+                    Some((_, argument)) => Scope::empty().evaluate(argument),
+                    None if self.parameters.contains(&id) => None,
+                    None => Some(Value::Path(path.to_token_stream().to_string())),
+                },
+                None => Some(Value::Path(path.to_token_stream().to_string())),
+            },
+            syn::Expr::Paren(syn::ExprParen { expr, .. })
+            | syn::Expr::Group(syn::ExprGroup { expr, .. }) => self.evaluate(expr),
+            syn::Expr::Unary(syn::ExprUnary { op, expr, .. }) => match (op, self.evaluate(expr)?) {
+                (syn::UnOp::Not(_), Value::Bool(b)) => Some(Value::Bool(!b)),
+                (syn::UnOp::Neg(_), Value::Int(i)) => i.checked_neg().map(Value::Int),
+                _ => None,
+            },
+            syn::Expr::Binary(syn::ExprBinary {
+                left, op, right, ..
+            }) => {
+                let lhs = self.evaluate(left);
+                let rhs = self.evaluate(right);
+
+                let result = match op {
+                    syn::BinOp::And(_) => match (as_bool(lhs), as_bool(rhs)) {
+                        (Some(false), _) | (_, Some(false)) => false,
+                        (Some(true), Some(true)) => true,
+                        _ => return None,
+                    },
+                    syn::BinOp::Or(_) => match (as_bool(lhs), as_bool(rhs)) {
+                        (Some(true), _) | (_, Some(true)) => true,
+                        (Some(false), Some(false)) => false,
+                        _ => return None,
+                    },
+                    syn::BinOp::Eq(_) => equals(&lhs?, &rhs?)?,
+                    syn::BinOp::Ne(_) => !equals(&lhs?, &rhs?)?,
+                    syn::BinOp::Lt(_) => compare(&lhs?, &rhs?)? == Ordering::Less,
+                    syn::BinOp::Le(_) => compare(&lhs?, &rhs?)? != Ordering::Greater,
+                    syn::BinOp::Gt(_) => compare(&lhs?, &rhs?)? == Ordering::Greater,
+                    syn::BinOp::Ge(_) => compare(&lhs?, &rhs?)? != Ordering::Less,
+                    _ => return None,
+                };
+
+                Some(Value::Bool(result))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use proc_macro2::Span;
+
+use crate::attribute::{Fn, MacroAttribute, ParameterizedList};
+
+/// An error of the macro at `span`, prefixed like the other errors of this crate.
+pub fn error(span: Span, message: impl std::fmt::Display) -> syn::Error {
+    syn::Error::new(span, format!("parameterized-macro: error: {}", message))
+}
+
+/// The given options which only apply to test cases which are run as tests: those which run the
+/// test cases with the built-in test runner, and those which check the values they return.
+pub fn test_options(argument_lists: &ParameterizedList) -> Vec<(&'static str, Span)> {
+    let options = [
+        (
+            "harness",
+            argument_lists.harness.map(|harness| harness.span),
+        ),
+        (
+            "aggregate",
+            argument_lists.aggregate.map(|aggregate| aggregate.span),
+        ),
+        (
+            "returns",
+            argument_lists
+                .returns
+                .as_ref()
+                .map(|returns| returns.returns_token.span),
+        ),
+        (
+            "expect",
+            argument_lists
+                .expect
+                .as_ref()
+                .map(|expect| expect.expect_token.span),
+        ),
+        (
+            "oracle",
+            argument_lists
+                .oracle
+                .as_ref()
+                .map(|oracle| oracle.oracle_token.span),
+        ),
+        (
+            "snapshot",
+            argument_lists
+                .snapshot
+                .as_ref()
+                .map(|snapshot| snapshot.snapshot_token.span),
+        ),
+        (
+            "compile_fail",
+            argument_lists
+                .compile_fail
+                .map(|compile_fail| compile_fail.span),
+        ),
+    ];
+
+    options
+        .iter()
+        .filter_map(|(option, span)| span.map(|span| (*option, span)))
+        .collect()
+}
+
+/// Reject the first of the given `options`, which can't be used together with `user`, for the
+/// given `reason`.
+pub fn reject_options(options: &[(&str, Span)], user: &str, reason: &str) -> syn::Result<()> {
+    match options.first() {
+        Some((option, span)) => Err(error(
+            *span,
+            format!(
+                "`{}` can't be used together with {}, {}",
+                option, user, reason
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Reject a test macro, `#[ignore]` or `#[should_panic]` on the test function, which can't be
+/// used together with `user`, since its test cases are not run as tests; `reason` explains who
+/// runs them instead.
+pub fn reject_test_attributes(f: &Fn, user: &str, reason: &str) -> syn::Result<()> {
+    for attr in &f.attrs {
+        match attr {
+            MacroAttribute::UseTestMacro(meta) => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    format!(
+                        "parameterized-macro: error: A custom test macro can't be used together with {}, {}",
+                        user, reason
+                    ),
+                ))
+            }
+            MacroAttribute::Unrelated(attr)
+                if attr.path().is_ident("ignore") || attr.path().is_ident("should_panic") =>
+            {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    format!(
+                        "parameterized-macro: error: A test attribute can't be used together with {}",
+                        user
+                    ),
+                ))
+            }
+            MacroAttribute::Unrelated(_) => {}
+        }
+    }
+
+    Ok(())
+}
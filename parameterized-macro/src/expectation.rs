@@ -0,0 +1,264 @@
+use proc_macro2::TokenStream;
+use syn::punctuated::Punctuated;
+use syn::visit_mut::VisitMut;
+
+use crate::attribute::{Fn, ParameterizedList, SnapshotFormat};
+use crate::diagnostics::error;
+use crate::generation::{function_arguments, returns_unit};
+use crate::generics::Substitution;
+use crate::tests::display;
+
+/// The expected outcome of each test case, which is checked against the value returned by the
+/// test function, instead of asserting within the test function itself.
+pub enum Expectation<'ctx> {
+    /// `returns = { .. }`: the returned value should equal the expected value of the test case
+    Returns(&'ctx Punctuated<syn::Expr, Token![,]>),
+    /// `expect = { .. }`: the returned value should match the pattern of the test case
+    Matches(&'ctx Punctuated<syn::Pat, Token![,]>),
+    /// `oracle = path`: the returned value should equal the value returned by the oracle, when
+    /// it's called with clones of the same arguments
+    Oracle {
+        path: &'ctx syn::Path,
+        arguments: Vec<&'ctx syn::Ident>,
+    },
+    /// `snapshot`: the returned value should equal the snapshot of the test case, which is
+    /// stored under `tests/snapshots/<module path>/<case>.snap`
+    Snapshot {
+        format: SnapshotFormat,
+        module_path: TokenStream,
+    },
+    /// `compile_fail`: the returned value is the source of the test case, which should fail to
+    /// compile, with the expected stderr of the test case; the source may use the crate under
+    /// test
+    CompileFail { module_path: TokenStream },
+}
+
+impl<'ctx> Expectation<'ctx> {
+    pub fn new(
+        argument_lists: &'ctx ParameterizedList,
+        f: &'ctx Fn,
+        amount_of_test_cases: usize,
+    ) -> syn::Result<Option<Self>> {
+        // (expectation, option, span, amount of expected outcomes)
+        let mut given = Vec::new();
+
+        if let Some(returns) = &argument_lists.returns {
+            given.push((
+                Expectation::Returns(&returns.values),
+                "returns",
+                returns.returns_token.span,
+                Some(returns.values.len()),
+            ));
+        }
+
+        if let Some(expect) = &argument_lists.expect {
+            given.push((
+                Expectation::Matches(&expect.patterns),
+                "expect",
+                expect.expect_token.span,
+                Some(expect.patterns.len()),
+            ));
+        }
+
+        if let Some(oracle) = &argument_lists.oracle {
+            given.push((
+                // the `#[case_index]` parameter isn't an input of the oracle
+                Expectation::Oracle {
+                    path: &oracle.path,
+                    arguments: function_arguments(f)?
+                        .0
+                        .into_iter()
+                        .map(|(identifier, _)| identifier)
+                        .collect(),
+                },
+                "oracle",
+                oracle.oracle_token.span,
+                None,
+            ));
+        }
+
+        // the path of the module generated for the test function, which tells apart the files
+        // of test functions with the same name in different modules; the check is made within
+        // the module, or next to the test function with `flat`
+        let module_path = match &argument_lists.flat {
+            Some(_) => {
+                let name = f.item_fn.sig.ident.to_string();
+                quote::quote!(::core::concat!(::core::module_path!(), "::", #name))
+            }
+            None => quote::quote!(::core::module_path!()),
+        };
+
+        if let Some(snapshot) = &argument_lists.snapshot {
+            given.push((
+                Expectation::Snapshot {
+                    format: snapshot.format,
+                    module_path: module_path.clone(),
+                },
+                "snapshot",
+                snapshot.snapshot_token.span,
+                None,
+            ));
+        }
+
+        if let Some(compile_fail) = &argument_lists.compile_fail {
+            given.push((
+                Expectation::CompileFail { module_path },
+                "compile_fail",
+                compile_fail.span,
+                None,
+            ));
+        }
+
+        if let Some((_, option, span, _)) = given.get(1) {
+            return Err(error(
+                *span,
+                format!("`{}` can't be used together with `{}`", option, given[0].1),
+            ));
+        }
+
+        let (expectation, option, span, amount) = match given.pop() {
+            Some(given) => given,
+            None => return Ok(None),
+        };
+
+        if returns_unit(f.return_type()) {
+            return Err(error(
+                span,
+                format!(
+                    "`{}` can only be used with a test function which returns a value",
+                    option
+                ),
+            ));
+        }
+
+        match amount {
+            Some(amount) if amount != amount_of_test_cases => Err(error(
+                span,
+                format!(
+                    "Each test case should have one expected return value.\n\
+                        Expected {} values for `{}`, but got: {}",
+                    amount_of_test_cases, option, amount
+                ),
+            )),
+            _ => Ok(Some(expectation)),
+        }
+    }
+
+    /// Generate the check of the value returned by the `invocation` of the test function,
+    /// against the expected outcome of the `ith` test case.
+    ///
+    /// The `substitution` is applied to the expected outcome of a parameterized method, which
+    /// may refer to `Self`.
+    pub fn generate_check(
+        &self,
+        invocation: TokenStream,
+        ith: usize,
+        substitution: Option<&mut Substitution>,
+    ) -> TokenStream {
+        match self {
+            Expectation::Returns(values) => {
+                let expected = match substitution {
+                    Some(substitution) => {
+                        substitution.apply(&values[ith], VisitMut::visit_expr_mut)
+                    }
+                    None => values[ith].clone(),
+                };
+
+                quote::quote! {
+                    match (#invocation, #expected) {
+                        (returned, expected) => ::core::assert_eq!(
+                            returned,
+                            expected,
+                            "the value returned by the test function (left) differs from the expected value (right)",
+                        ),
+                    }
+                }
+            }
+            Expectation::Matches(patterns) => {
+                let pattern = match substitution {
+                    Some(substitution) => {
+                        substitution.apply(&patterns[ith], VisitMut::visit_pat_mut)
+                    }
+                    None => patterns[ith].clone(),
+                };
+                let message = format!(
+                    "the value returned by the test function doesn't match the expected pattern `{}`\n  returned: {{:?}}",
+                    escape_braces(&display(&pattern)),
+                );
+
+                quote::quote! {
+                    match #invocation {
+                        #pattern => {}
+                        #[allow(unreachable_patterns)]
+                        returned => ::core::panic!(#message, returned),
+                    }
+                }
+            }
+            Expectation::Oracle { path, arguments } => {
+                let oracle = syn::Expr::Path(syn::ExprPath {
+                    attrs: Vec::new(),
+                    qself: None,
+                    path: (*path).clone(),
+                });
+                let oracle = match substitution {
+                    Some(substitution) => substitution.apply(&oracle, VisitMut::visit_expr_mut),
+                    None => oracle,
+                };
+                let message = format!(
+                    "the value returned by the test function (left) differs from the value returned by the oracle `{}` (right)",
+                    escape_braces(&display(*path)),
+                );
+
+                // the arguments are moved into the test function, so the oracle, which is called
+                // first, is given clones of them; a missing `Clone` implementation is reported
+                // at the parameter
+                let arguments = arguments.iter().map(|argument| {
+                    quote::quote_spanned!(argument.span()=> ::core::clone::Clone::clone(&#argument))
+                });
+
+                quote::quote! {
+                    match (#oracle(#(#arguments),*), #invocation) {
+                        (expected, returned) => ::core::assert_eq!(returned, expected, #message),
+                    }
+                }
+            }
+            Expectation::Snapshot {
+                format,
+                module_path,
+            } => {
+                let case = format!("case_{}", ith);
+                let format = match format {
+                    SnapshotFormat::Debug => "{:#?}",
+                    SnapshotFormat::Display => "{}",
+                };
+
+                quote::quote! {
+                    ::parameterized::snapshot::assert_snapshot(
+                        ::core::env!("CARGO_MANIFEST_DIR"),
+                        #module_path,
+                        #case,
+                        &::std::format!(#format, #invocation),
+                    )
+                }
+            }
+            Expectation::CompileFail { module_path } => {
+                let case = format!("case_{}", ith);
+
+                quote::quote! {
+                    ::parameterized::compile_fail::assert_compile_fail(
+                        ::core::env!("CARGO_MANIFEST_DIR"),
+                        ::core::env!("CARGO_PKG_NAME"),
+                        #module_path,
+                        #case,
+                        ::core::convert::AsRef::<str>::as_ref(&#invocation),
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Escape the braces of a literal text which is used in a format string.
+fn escape_braces(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
@@ -0,0 +1,137 @@
+use proc_macro2::{Span, TokenStream};
+
+use crate::attribute::{Fn, MacroAttribute, Method, ParameterizedList};
+use crate::generation;
+
+/// Attributes which only make sense on a test case, and are therefore moved from a
+/// parameterized method to its generated test cases.
+const TEST_ATTRIBUTES: &[&str] = &["parameterized_macro", "should_panic", "ignore"];
+
+/// Generate the test cases for the parameterized methods of an `impl` block. The `impl` block
+/// itself is kept, without the `#[parameterized(..)]` attributes of its methods.
+pub fn generate(arguments: ParameterizedList, item_impl: syn::ItemImpl) -> proc_macro::TokenStream {
+    expand(&arguments, item_impl)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(arguments: &ParameterizedList, mut item_impl: syn::ItemImpl) -> syn::Result<TokenStream> {
+    if !arguments.args.is_empty()
+        || arguments.constraint.is_some()
+        || arguments.exclusions.is_some()
+        || arguments.sample.is_some()
+        || arguments.seed.is_some()
+        || arguments.flat.is_some()
+        || arguments.module.is_some()
+        || arguments.harness.is_some()
+        || arguments.aggregate.is_some()
+        || arguments.const_eval.is_some()
+        || arguments.returns.is_some()
+        || arguments.expect.is_some()
+        || arguments.oracle.is_some()
+        || arguments.snapshot.is_some()
+        || arguments.compile_fail.is_some()
+        || arguments.runtime.is_some()
+        || arguments.test_macro.is_some()
+    {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "parameterized-macro: error: The #[parameterized] attribute of an `impl` block may only specify a `fixture`; \
+                the test cases should be given to its methods instead",
+        ));
+    }
+
+    if !item_impl.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_impl.generics,
+            "parameterized-macro: error: Parameterized methods can't be defined in a generic `impl` block",
+        ));
+    }
+
+    let self_ty = item_impl.self_ty.as_ref().clone();
+    let mut modules = Vec::new();
+
+    for item in item_impl.items.iter_mut() {
+        let method = match item {
+            syn::ImplItem::Fn(method) => method,
+            _ => continue,
+        };
+
+        let position = method
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("parameterized"));
+
+        let attr = match position {
+            Some(position) => method.attrs.remove(position),
+            None => continue,
+        };
+
+        let lists: ParameterizedList = match &attr.meta {
+            syn::Meta::List(list) => syn::parse2(list.tokens.clone())?,
+            _ => syn::parse2(TokenStream::new())?,
+        };
+
+        let receiver = match method.sig.receiver() {
+            Some(receiver) if receiver.colon_token.is_some() => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "parameterized-macro: error: A parameterized method should take `self`, `&self` or `&mut self`",
+                ))
+            }
+            receiver => receiver.cloned(),
+        };
+
+        // the test cases receive all attributes of the method, but attributes which are
+        // only meaningful for tests are removed from the method itself
+        let attrs = method
+            .attrs
+            .iter()
+            .cloned()
+            .map(MacroAttribute::from_attribute)
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        method.attrs.retain(|attr| {
+            !TEST_ATTRIBUTES
+                .iter()
+                .any(|name| attr.path().is_ident(name))
+        });
+
+        let mut func = Fn {
+            attrs,
+            item_fn: syn::ItemFn {
+                attrs: Vec::new(),
+                vis: method.vis.clone(),
+                sig: method.sig.clone(),
+                block: Box::new(method.block.clone()),
+            },
+            method: Some(Method {
+                self_ty: self_ty.clone(),
+                receiver,
+                constructor: lists
+                    .fixture
+                    .as_ref()
+                    .or(arguments.fixture.as_ref())
+                    .map(|fixture| fixture.constructor.clone()),
+            }),
+        };
+
+        // the `#[case_index]` parameter is an argument of the method like any other
+        for fn_arg in method.sig.inputs.iter_mut() {
+            if let syn::FnArg::Typed(pat_type) = fn_arg {
+                pat_type
+                    .attrs
+                    .retain(|attr| !attr.path().is_ident(generation::CASE_INDEX));
+            }
+        }
+
+        func.add_test_macro(lists.test_macro.as_ref())?;
+        modules.push(generation::expand(&lists, &func)?);
+    }
+
+    Ok(quote::quote! {
+        #item_impl
+
+        #(#modules)*
+    })
+}
@@ -1,18 +1,269 @@
 use proc_macro2::{Span, TokenStream};
+use syn::spanned::Spanned;
+use syn::visit_mut::VisitMut;
 
-use crate::attribute::{Fn, ParameterizedList};
-use crate::tests::TestCases;
+use crate::attribute::{
+    Fn, MacroAttribute, Method, ParameterKind, ParameterizedList, Runtime, RuntimeKind,
+};
+use crate::bench::{bencher, Bench};
+use crate::const_eval::ConstEval;
+use crate::constraint::{Constraints, Verdict};
+use crate::expectation::Expectation;
+use crate::generics::{GenericParameters, ImplTraitParameters};
+use crate::harness::Harness;
+use crate::sampling::Sampling;
+use crate::tests::{Argument, TestCases};
 
-pub fn generate(argument_lists: ParameterizedList, func: Fn) -> proc_macro::TokenStream {
+pub fn generate(argument_lists: ParameterizedList, mut func: Fn) -> proc_macro::TokenStream {
+    func.add_test_macro(argument_lists.test_macro.as_ref())
+        .and_then(|_| expand(&argument_lists, &func))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+pub fn expand(argument_lists: &ParameterizedList, func: &Fn) -> syn::Result<TokenStream> {
     // Map the given arguments by their identifier
-    let values = into_argument_map(&argument_lists);
-    let args = function_arguments(&func);
+    let values = into_argument_map(argument_lists);
+    let (args, case_index) = function_arguments(func)?;
     let amount_of_test_cases = values.amount_of_test_cases().unwrap_or_default();
 
-    let generated_test_cases =
-        (0..amount_of_test_cases).map(|i| generate_test_case(args.as_slice(), &values, i, &func));
+    check_reserved_keys(argument_lists, &args)?;
+    check_case_index(argument_lists, case_index)?;
+
+    if let (Some(fixture), None) = (&argument_lists.fixture, &func.method) {
+        return Err(syn::Error::new(
+            fixture.fixture_token.span,
+            "parameterized-macro: error: A `fixture` can only be given to a method, within an `impl` block annotated with #[parameterized]",
+        ));
+    }
+
+    let constraints = Constraints::new(argument_lists, &args)?;
+    let generics = GenericParameters::new(func, argument_lists)?;
+
+    // Cases which violate a constraint at expansion time are dropped, the others may have to be
+    // skipped at runtime instead
+    let mut selected_test_cases = Vec::new();
+    for i in 0..amount_of_test_cases {
+        match constraints.check(&values, i) {
+            Verdict::Keep => selected_test_cases.push((i, None)),
+            Verdict::Drop => {}
+            Verdict::Runtime(_) if func.constness().is_some() => {
+                return Err(syn::Error::new_spanned(
+                    func.constness(),
+                    format!(
+                        "parameterized-macro: error: Unable to check the constraints of case #{} at expansion time, \
+                            and a `const fn` test case can't be skipped at runtime",
+                        i
+                    ),
+                ))
+            }
+            Verdict::Runtime(skip_if) => selected_test_cases.push((i, Some(skip_if))),
+        }
+    }
+
+    let expectation = Expectation::new(argument_lists, func, amount_of_test_cases)?;
+    let layout = Layout::new(argument_lists, func)?;
+    let harness = Harness::new(argument_lists, func)?;
+    let bench = Bench::new(argument_lists, func)?;
+    let const_eval = ConstEval::new(argument_lists, func)?;
+    check_asyncness(argument_lists, func)?;
+    let mut module_items = Vec::new();
+
+    if let Some(sample) = &argument_lists.sample {
+        let sampling = Sampling::new(sample, argument_lists.seed.as_ref())?;
+
+        module_items.push(sampling.generate_items(&layout, func, selected_test_cases.len()));
+        selected_test_cases = sampling.select(selected_test_cases);
+    }
+
+    let context = Context {
+        parameters: &args,
+        case_index,
+        test_cases: &values,
+        f: func,
+        generics: &generics,
+        layout: &layout,
+        harness: harness.as_ref(),
+        bench: bench.as_ref(),
+        const_eval: const_eval.as_ref(),
+        runtime: argument_lists.runtime.as_ref(),
+        expectation: expectation.as_ref(),
+    };
+
+    // the test function is defined once, and called by each of the test cases; a method is
+    // already defined within its `impl` block
+    if func.method.is_none() {
+        module_items.push(generate_helper(&context));
+    }
+
+    if let Some(harness) = &harness {
+        let cases = selected_test_cases
+            .iter()
+            .map(|(i, _)| (*i, layout.item_name(func, &format!("case_{}", i))))
+            .collect::<Vec<_>>();
+
+        module_items.push(harness.generate_registry(
+            func,
+            &layout,
+            argument_lists,
+            &values,
+            &cases,
+        ));
+    }
+
+    if let Some(bench) = &bench {
+        let cases = selected_test_cases
+            .iter()
+            .map(|(i, _)| (*i, layout.item_name(func, &format!("case_{}", i))))
+            .collect::<Vec<_>>();
+
+        module_items.push(bench.generate_registry(argument_lists, &values, &cases));
+    }
+
+    let generated_test_cases = selected_test_cases
+        .into_iter()
+        .map(|(i, skip_if)| generate_test_case(&context, i, skip_if));
+
+    Ok(generate_module(
+        module_items.into_iter().chain(generated_test_cases),
+        &context,
+    ))
+}
+
+/// The inputs shared by each of the generated test cases.
+struct Context<'ctx> {
+    parameters: &'ctx [FnArgPair<'ctx>],
+    /// The parameter annotated with `#[case_index]`, which receives the index of the test case
+    case_index: Option<FnArgPair<'ctx>>,
+    test_cases: &'ctx TestCases<'ctx>,
+    f: &'ctx Fn,
+    generics: &'ctx GenericParameters<'ctx>,
+    layout: &'ctx Layout,
+    /// Set if the test cases are registered with the built-in test runner, or aggregated into a
+    /// single test, instead of being marked with `#[test]`
+    harness: Option<&'ctx Harness>,
+    /// Set if a benchmark is generated for each test case, instead of a test
+    bench: Option<&'ctx Bench>,
+    /// Set if the test cases are evaluated at compile time, instead of being run as tests
+    const_eval: Option<&'ctx ConstEval>,
+    /// The async runtime which runs the test cases of an `async` test function, if given
+    runtime: Option<&'ctx Runtime>,
+    /// Set if the value returned by the test function is checked by the test cases
+    expectation: Option<&'ctx Expectation<'ctx>>,
+}
+
+impl Context<'_> {
+    /// Whether the generated items are only compiled with `cfg(test)`. Test and bench targets
+    /// without the default test harness are not compiled with `cfg(test)`, while test cases
+    /// which are evaluated at compile time are checked by every build.
+    fn is_test_only(&self) -> bool {
+        match self.harness {
+            Some(harness) if !harness.is_aggregate() => false,
+            _ => self.bench.is_none() && self.const_eval.is_none(),
+        }
+    }
+}
 
-    generate_module(generated_test_cases, &func).into()
+/// Where the generated test cases are placed, and how they're named.
+pub enum Layout {
+    /// In a module named after the test function (or the given `module = name`), which imports
+    /// the items of its parent with `use super::*`
+    Module(syn::Ident),
+    /// As siblings of the test function, with names prefixed by the name of the test function.
+    ///
+    /// Unlike a module, this also works when the test function is defined within a block, e.g.
+    /// in a function body, where `use super::*` can't see the locally defined items.
+    Flat,
+}
+
+impl Layout {
+    fn new(argument_lists: &ParameterizedList, f: &Fn) -> syn::Result<Self> {
+        match (&argument_lists.flat, &argument_lists.module) {
+            (Some(_), Some(module)) => Err(syn::Error::new(
+                module.module_token.span,
+                "parameterized-macro: error: A `module` name can't be given together with `flat`, since no module is generated",
+            )),
+            (Some(_), None) => Ok(Layout::Flat),
+            (None, Some(module)) => Ok(Layout::Module(module.name.clone())),
+            (None, None) => Ok(Layout::Module(f.item_fn.sig.ident.clone())),
+        }
+    }
+
+    /// The name of an item generated for the test function `f`, e.g. `case_0` or `add5_case_0`.
+    pub fn item_name(&self, f: &Fn, name: &str) -> syn::Ident {
+        match self {
+            Layout::Module(_) => syn::Ident::new(name, Span::call_site()),
+            Layout::Flat => syn::Ident::new(
+                &format!("{}_{}", f.item_fn.sig.ident, name),
+                Span::call_site(),
+            ),
+        }
+    }
+
+    /// The path of a test case, as shown by the test harness.
+    fn test_path(&self, identifier: &syn::Ident) -> String {
+        match self {
+            Layout::Module(module) => format!("{}::{}", module, identifier),
+            Layout::Flat => identifier.to_string(),
+        }
+    }
+}
+
+/// Options of the `#[parameterized(..)]` attribute are given in the same way as the
+/// arguments of a parameter, so a parameter may not share its name with a given option.
+fn check_reserved_keys(argument_lists: &ParameterizedList, args: &[FnArgPair]) -> syn::Result<()> {
+    for key in &argument_lists.reserved_keys {
+        if args.iter().any(|(id, _)| *id == key) {
+            return Err(crate::attribute::reserved_key(key));
+        }
+    }
+
+    Ok(())
+}
+
+/// The `#[case_index]` parameter receives the index of the test case, so it can't be given
+/// arguments of its own.
+fn check_case_index(
+    argument_lists: &ParameterizedList,
+    case_index: Option<FnArgPair>,
+) -> syn::Result<()> {
+    let (case_index, _) = match case_index {
+        Some(case_index) => case_index,
+        None => return Ok(()),
+    };
+
+    match argument_lists.args.iter().find(|list| list.id == *case_index) {
+        Some(list) => Err(syn::Error::new(
+            list.id.span(),
+            format!(
+                "parameterized-macro: error: The parameter `{}` is annotated with #[case_index], and receives the index of each test case; \
+                    it can't be given arguments",
+                case_index
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// An `async` test function is run by its test macro, or by the given `runtime`; without either,
+/// its test cases block on the built-in executor, `parameterized::block_on`.
+fn check_asyncness(argument_lists: &ParameterizedList, f: &Fn) -> syn::Result<()> {
+    let test_macro = f.attrs.iter().find_map(|attr| match attr {
+        MacroAttribute::UseTestMacro(meta) => Some(meta),
+        MacroAttribute::Unrelated(_) => None,
+    });
+
+    match (&argument_lists.runtime, f.asyncness(), test_macro) {
+        (Some(runtime), None, _) => Err(syn::Error::new(
+            runtime.runtime_token.span,
+            "parameterized-macro: error: A `runtime` can only be given to an `async` test function",
+        )),
+        (Some(_), Some(_), Some(test_macro)) => Err(syn::Error::new_spanned(
+            test_macro,
+            "parameterized-macro: error: A custom test macro can't be used together with `runtime`, which already provides the test macro",
+        )),
+        _ => Ok(()),
+    }
 }
 
 /// Transform an AttributeArgList into an ordered map which orders its
@@ -24,38 +275,85 @@ fn into_argument_map(arguments: &ParameterizedList) -> TestCases<'_> {
         .iter()
         .fold(TestCases::empty(), |mut acc, args| {
             let identifier = &args.id;
-            let exprs = args.param_args.iter().collect::<Vec<&syn::Expr>>();
+            let arguments = match args.kind {
+                ParameterKind::Value => args.param_args.iter().map(Argument::Value).collect(),
+                ParameterKind::Type => args.type_args.iter().map(Argument::Type).collect(),
+                ParameterKind::Const => args.param_args.iter().map(Argument::Const).collect(),
+            };
 
-            acc.insert(identifier, exprs);
+            acc.insert(identifier, arguments);
 
             acc
         })
 }
 
-type FnArgPair<'ctx> = (&'ctx syn::Ident, &'ctx Box<syn::Type>);
+pub type FnArgPair<'ctx> = (&'ctx syn::Ident, &'ctx Box<syn::Type>);
 
-/// Returns the vector of all typed parameter pairs for a given function.
-fn function_arguments(f: &Fn) -> Vec<FnArgPair<'_>> {
-    f.item_fn.sig.inputs.iter().map(|fn_arg| {
+/// Attribute of a parameter of the test function, which receives the index of the test case.
+pub const CASE_INDEX: &str = "case_index";
+
+/// Returns the vector of all typed parameter pairs for a given function, which receive their
+/// arguments from the attribute, and the parameter annotated with `#[case_index]`, if any.
+///
+/// The receiver of a parameterized method is not included, since it's provided by the fixture.
+pub fn function_arguments(f: &Fn) -> syn::Result<(Vec<FnArgPair<'_>>, Option<FnArgPair<'_>>)> {
+    let mut arguments = Vec::new();
+    let mut case_index = None;
+
+    for fn_arg in &f.item_fn.sig.inputs {
         match fn_arg {
-            syn::FnArg::Typed(syn::PatType { pat, ty, .. }) => match pat.as_ref() {
-                syn::Pat::Ident(syn::PatIdent { ident, .. }) => (ident, ty) ,
-                _ => panic!("parameterized-macro: error: No identifier found for test case")
+            syn::FnArg::Typed(syn::PatType { attrs, pat, ty, .. }) => match pat.as_ref() {
+                syn::Pat::Ident(syn::PatIdent { ident, .. })
+                    if attrs.iter().any(|attr| attr.path().is_ident(CASE_INDEX)) =>
+                {
+                    if case_index.replace((ident, ty)).is_some() {
+                        return Err(syn::Error::new_spanned(
+                            ident,
+                            "parameterized-macro: error: Only a single parameter may be annotated with #[case_index]",
+                        ));
+                    }
+                }
+                syn::Pat::Ident(syn::PatIdent { ident, .. }) => arguments.push((ident, ty)),
+                _ => panic!("parameterized-macro: error: No identifier found for test case"),
+            },
+            syn::FnArg::Receiver(_) if f.method.is_some() => {}
+            syn::FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "parameterized-macro: error: Unexpected receiver found in test case function arguments; \
+                        a parameterized method should be defined in an `impl` block annotated with #[parameterized]",
+                ))
             }
-            _ => panic!("parameterized-macro: error: Unexpected receiver found in test case function arguments")
         }
+    }
 
-    }).collect::<Vec<_>>()
+    Ok((arguments, case_index))
 }
 
-fn generate_module<I: Iterator<Item = TokenStream>>(test_cases: I, f: &Fn) -> TokenStream {
-    let name = &f.item_fn.sig.ident;
-    let vis = &f.item_fn.vis;
-    let mod_ident = syn::Ident::new(&format!("{}", name), name.span());
+fn generate_module<I: Iterator<Item = TokenStream>>(
+    test_cases: I,
+    context: &Context,
+) -> TokenStream {
+    let mod_ident = match context.layout {
+        Layout::Module(name) => name,
+        Layout::Flat => {
+            return quote::quote! {
+                #(#test_cases)*
+            }
+        }
+    };
+
+    let vis = &context.f.item_fn.vis;
+
+    let cfg_test = if context.is_test_only() {
+        Some(quote::quote!(#[cfg(test)]))
+    } else {
+        None
+    };
 
     // we need to include `use super::*` since we put the test cases in a new module
     quote::quote! {
-        #[cfg(test)]
+        #cfg_test
         #vis mod #mod_ident {
             use super::*;
 
@@ -65,57 +363,429 @@ fn generate_module<I: Iterator<Item = TokenStream>>(test_cases: I, f: &Fn) -> To
 }
 
 /// Generate a single test case from the attribute inputs.
-fn generate_test_case(
-    parameters: &[FnArgPair],
-    test_cases: &TestCases,
-    i: usize,
-    f: &Fn,
-) -> TokenStream {
-    let constness = f.constness();
+fn generate_test_case(context: &Context, i: usize, skip_if: Option<TokenStream>) -> TokenStream {
+    let Context {
+        parameters,
+        case_index,
+        test_cases,
+        f,
+        generics,
+        layout,
+        harness,
+        bench,
+        const_eval,
+        runtime,
+        expectation,
+    } = *context;
+
     let asyncness = f.asyncness();
     let unsafety = f.unsafety();
     let visibility = f.visibility();
-    let identifier = syn::Ident::new(&format!("case_{}", i), Span::call_site());
-    let return_type = f.return_type();
-    let body = f.body();
+    let identifier = layout.item_name(f, &format!("case_{}", i));
+
+    // The generic parameters of the test function don't exist in the generated test case, so
+    // they're replaced by the generic arguments of this case
+    let mut substitution = generics.substitution(test_cases, i);
+    let return_type = substitution.apply(f.return_type(), VisitMut::visit_return_type_mut);
 
     // Construction let bindings for all parameters
-    let bindings = parameters.iter().map(|(identifier, ty)| {
-        let expr = test_cases.get(identifier, i);
+    let mut bindings = parameters
+        .iter()
+        .map(|(identifier, ty)| {
+            let expr = test_cases.get(identifier, i);
+            let ty = substitution.binding_type(ty);
 
-        generate_binding(identifier, ty, expr)
-    });
+            // arguments of a method may refer to `Self`, which must be replaced as well
+            if f.method.is_some() {
+                let expr = substitution.apply(expr, VisitMut::visit_expr_mut);
+                generate_binding(identifier, ty.as_ref(), &expr)
+            } else {
+                generate_binding(identifier, ty.as_ref(), expr)
+            }
+        })
+        .collect::<Vec<_>>();
 
-    let (use_test_macro, unrelated_attributes): (Vec<_>, Vec<_>) =
-        f.attrs.iter().partition(|&m| m.is_use_test_macro());
+    if let Some((identifier, ty)) = case_index {
+        let index = proc_macro2::Literal::usize_unsuffixed(i);
+        bindings.push(quote::quote!(let #identifier: #ty = #index;));
+    }
+
+    // Each test case only binds its arguments, and calls the test function with them
+    let mut invocation = generate_invocation(f, layout, generics.arguments(test_cases, i));
+
+    // A test case which checks the returned value doesn't return it itself
+    let mut return_type = return_type;
+    if let Some(expectation) = expectation {
+        // the expected outcome of a method may refer to `Self`, which must be replaced as well
+        let substitution = f.method.as_ref().map(|_| &mut substitution);
+
+        invocation = expectation.generate_check(invocation, i, substitution);
+        return_type = syn::ReturnType::Default;
+    }
+
+    if let Some(const_eval) = const_eval {
+        let case_span = parameters
+            .first()
+            .map_or_else(Span::call_site, |(identifier, _)| {
+                test_cases.get_argument(identifier, i).span()
+            });
+
+        return const_eval.generate_case(f, &bindings, invocation, case_span);
+    }
 
-    if use_test_macro.len() > 1 {
-        panic!("parameterized-macro: the #[parameterized_macro(..)] attribute should not be present more than once!");
+    if let Some(bench) = bench {
+        let unrelated_attributes = f.attrs.iter().map(|attr| attr.quoted());
+        let body = bench.generate_body(f, &bindings, invocation);
+        let bencher = bencher();
+        // a benchmark which violates a constraint at runtime isn't timed
+        let skip = skip_if.map(|condition| {
+            quote::quote! {
+                #[allow(unused_variables)]
+                let __parameterized_skip = {
+                    #(#bindings)*
+                    #condition
+                };
+
+                if __parameterized_skip {
+                    return;
+                }
+            }
+        });
+
+        return quote::quote! {
+            #(#unrelated_attributes)*
+            #visibility fn #identifier(#bencher: &mut ::parameterized::bench::Bencher) {
+                #skip
+
+                #body
+            }
+        };
     }
 
-    let unrelated_attributes = unrelated_attributes.iter().map(|attr| attr.quoted());
+    let body = generate_guard(context, i, &identifier, invocation);
+
+    let skip = skip_if
+        .map(|condition| generate_skip(condition, &layout.test_path(&identifier), &return_type));
 
-    let test_macro = if use_test_macro.is_empty() {
-        quote::quote!(#[test])
+    let (use_test_macro, unrelated_attributes): (Vec<_>, Vec<_>) =
+        f.attrs.iter().partition(|&m| m.is_use_test_macro());
+
+    let unrelated_attributes = unrelated_attributes
+        .iter()
+        .filter(|attr| harness.is_none() || !Harness::is_test_attribute(attr))
+        .map(|attr| attr.quoted());
+
+    let test_macro = if harness.is_some() {
+        None
+    } else if let Some(runtime) = runtime {
+        Some(generate_runtime_test_macro(runtime))
+    } else if use_test_macro.is_empty() {
+        Some(quote::quote!(#[test]))
     } else {
-        let meta = use_test_macro[0];
-        let meta = meta.quoted();
-        quote::quote!(#[#meta])
+        // stacked test macros are applied in the order in which they're given
+        let test_macros = use_test_macro.iter().map(|attr| attr.quoted());
+        Some(quote::quote!(#(#test_macros)*))
     };
 
+    // the cases are not wrapped in a `#[cfg(test)]` module, so each is marked on its own
+    let cfg_test = match layout {
+        Layout::Module(_) => None,
+        Layout::Flat => Some(quote::quote!(#[cfg(test)])),
+    };
+
+    let mut body = quote::quote! {
+        #(#bindings)*
+
+        #skip
+
+        #body
+    };
+
+    // without a test macro which runs the async test case, it blocks on its body instead
+    let mut asyncness = asyncness;
+    let executor = match runtime.map(|runtime| runtime.kind) {
+        Some(RuntimeKind::Smol) => Some(quote::quote!(::smol::block_on)),
+        None if asyncness.is_some() && use_test_macro.is_empty() => {
+            Some(quote::quote!(::parameterized::block_on))
+        }
+        _ => None,
+    };
+
+    if let Some(executor) = executor {
+        body = quote::quote!(#executor(async move { #body }));
+        asyncness = None;
+    }
+
     quote::quote! {
+        #cfg_test
         #test_macro
         #(#unrelated_attributes)*
-        #constness #asyncness #unsafety #visibility fn #identifier() #return_type {
-            #(#bindings)*
-
+        #asyncness #unsafety #visibility fn #identifier() #return_type {
             #body
         }
     }
 }
 
-fn generate_binding(identifier: &syn::Ident, ty: &syn::Type, expr: &syn::Expr) -> TokenStream {
+/// Generate the test macro of the given async runtime.
+fn generate_runtime_test_macro(runtime: &Runtime) -> TokenStream {
+    let options = &runtime.options;
+
+    match runtime.kind {
+        RuntimeKind::Tokio if options.is_empty() => quote::quote!(#[::tokio::test]),
+        RuntimeKind::Tokio => quote::quote!(#[::tokio::test(#options)]),
+        RuntimeKind::AsyncStd => quote::quote!(#[::async_std::test]),
+        RuntimeKind::Smol => quote::quote!(#[test]),
+    }
+}
+
+/// Generate the test function itself, which is defined once, next to the test cases which
+/// call it. Only lint attributes are kept, since they may apply to its body.
+fn generate_helper(context: &Context) -> TokenStream {
+    let mut item_fn = context.generics.item_fn(context.f);
+
+    for fn_arg in item_fn.sig.inputs.iter_mut() {
+        if let syn::FnArg::Typed(pat_type) = fn_arg {
+            pat_type
+                .attrs
+                .retain(|attr| !attr.path().is_ident(CASE_INDEX));
+        }
+    }
+
+    item_fn.attrs = context
+        .f
+        .attrs
+        .iter()
+        .filter_map(|attr| match attr {
+            MacroAttribute::Unrelated(attr) if is_lint_attribute(attr) => Some(attr.clone()),
+            _ => None,
+        })
+        .collect();
+
+    // the cases are not wrapped in a `#[cfg(test)]` module, so neither is the test function
+    let cfg_test = match context.layout {
+        Layout::Flat if context.is_test_only() => Some(quote::quote!(#[cfg(test)])),
+        _ => None,
+    };
+
+    quote::quote! {
+        #cfg_test
+        #[allow(dead_code)]
+        #item_fn
+    }
+}
+
+fn is_lint_attribute(attr: &syn::Attribute) -> bool {
+    ["allow", "warn", "deny", "forbid", "expect"]
+        .iter()
+        .any(|lint| attr.path().is_ident(lint))
+}
+
+/// Generate a block which enters the `ith` test case before the test function is called, so
+/// it's available from `parameterized::current_case()`, and its arguments are reported if the
+/// test function panics or returns an `Err`.
+///
+/// The `Debug` representation of an argument is captured up front, since the argument is moved
+/// into the test function; arguments whose type doesn't implement `Debug` are reported by their
+/// expression only.
+fn generate_guard(
+    context: &Context,
+    i: usize,
+    identifier: &syn::Ident,
+    invocation: TokenStream,
+) -> TokenStream {
+    let parameters = context.parameters.iter().map(|(identifier, _)| {
+        let parameter = identifier.to_string();
+        let argument = context.test_cases.get_argument(identifier, i).display();
+        quote::quote!((#parameter, #argument))
+    });
+
+    let label = context.layout.test_path(identifier);
+    let total = context
+        .test_cases
+        .amount_of_test_cases()
+        .unwrap_or_default();
+    let case = quote::quote! {
+        ::parameterized::case::CaseContext::new(#i, #label, #total, &[#(#parameters),*])
+    };
+
+    // The built-in test runner reports the arguments of failing cases by itself
+    if context.harness.is_some() {
+        return quote::quote! {
+            {
+                let __parameterized_case = ::parameterized::case::Guard::enter_silently(#case);
+
+                #invocation
+            }
+        };
+    }
+
+    let values = context.parameters.iter().map(
+        |(identifier, _)| quote::quote!((&::parameterized::case::Value(&#identifier)).debug()),
+    );
+
+    quote::quote! {
+        {
+            let __parameterized_case = {
+                use ::parameterized::case::{Inspect as _, InspectFallback as _};
+                ::parameterized::case::Guard::enter(#case, ::std::vec![#(#values),*])
+            };
+
+            let __parameterized_outcome = #invocation;
+
+            {
+                use ::parameterized::case::{Failure as _, FailureFallback as _};
+                __parameterized_case.exit(
+                    (&::parameterized::case::Value(&__parameterized_outcome)).is_failure(),
+                );
+            }
+
+            __parameterized_outcome
+        }
+    }
+}
+
+/// Generate a call of the test function, with the bound arguments of a test case.
+///
+/// A parameterized method is called on a fixture constructed by the test case.
+fn generate_invocation(
+    f: &Fn,
+    layout: &Layout,
+    generic_arguments: Option<Vec<TokenStream>>,
+) -> TokenStream {
+    let ident = &f.item_fn.sig.ident;
+    let arguments = call_arguments(f);
+    let turbofish = generic_arguments.map(|arguments| quote::quote!(::<#(#arguments),*>));
+
+    let (item, callee, receiver) = match &f.method {
+        Some(method) => {
+            let self_ty = &method.self_ty;
+            let (fixture, receiver) = generate_fixture(method);
+            (fixture, quote::quote!(<#self_ty>::#ident), receiver)
+        }
+        // a parameter may shadow the test function, unless it's called by its path
+        None => match layout {
+            Layout::Module(_) => (TokenStream::new(), quote::quote!(self::#ident), None),
+            Layout::Flat => (TokenStream::new(), quote::quote!(#ident), None),
+        },
+    };
+
+    let mut call = quote::quote!(#callee #turbofish(#receiver #(#arguments),*));
+
+    if f.asyncness().is_some() {
+        call = quote::quote!(#call.await);
+    }
+
+    if f.unsafety().is_some() {
+        call = quote::quote!(unsafe { #call });
+    }
+
     quote::quote! {
-        let #identifier: #ty = #expr;
+        {
+            #item
+
+            #call
+        }
+    }
+}
+
+/// The arguments with which the test function is called, in the order of its parameters; these
+/// are bound by each test case under the name of their parameter.
+pub fn call_arguments(f: &Fn) -> Vec<&syn::Ident> {
+    f.item_fn
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|fn_arg| match fn_arg {
+            syn::FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                syn::Pat::Ident(pat_ident) => Some(&pat_ident.ident),
+                _ => None,
+            },
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// Generate the construction of the fixture of a parameterized method, and the receiver argument
+/// which passes it to the method.
+fn generate_fixture(method: &Method) -> (TokenStream, Option<TokenStream>) {
+    let receiver = match &method.receiver {
+        Some(receiver) => receiver,
+        None => return (TokenStream::new(), None),
+    };
+
+    let self_ty = &method.self_ty;
+    let fixture = syn::Ident::new("__parameterized_fixture", Span::call_site());
+
+    let constructor = match &method.constructor {
+        Some(constructor) => quote::quote!(#constructor()),
+        None => quote::quote!(<#self_ty as ::core::default::Default>::default()),
+    };
+
+    let (mutability, argument) = match (&receiver.reference, &receiver.mutability) {
+        (Some(_), Some(_)) => (quote::quote!(mut), quote::quote!(&mut #fixture,)),
+        (Some(_), None) => (TokenStream::new(), quote::quote!(&#fixture,)),
+        (None, _) => (TokenStream::new(), quote::quote!(#fixture,)),
+    };
+
+    let item = quote::quote! {
+        let #mutability #fixture: #self_ty = #constructor;
+    };
+
+    (item, Some(argument))
+}
+
+fn generate_binding(
+    identifier: &syn::Ident,
+    ty: Option<&syn::Type>,
+    expr: &syn::Expr,
+) -> TokenStream {
+    match ty {
+        Some(ty) => match ImplTraitParameters::check(ty, expr) {
+            Some(checked) => quote::quote! {
+                let #identifier = #checked;
+            },
+            None => quote::quote! {
+                let #identifier: #ty = #expr;
+            },
+        },
+        None => quote::quote! {
+            let #identifier = #expr;
+        },
+    }
+}
+
+/// Generate an early return for a test case which, at runtime, turns out to violate a constraint.
+///
+/// The marker is written to stderr directly, so it's not captured by the test harness.
+fn generate_skip(
+    condition: TokenStream,
+    test_path: &str,
+    return_type: &syn::ReturnType,
+) -> TokenStream {
+    let marker = format!("parameterized: {} skipped by constraint\n", test_path);
+
+    let return_value = if returns_unit(return_type) {
+        None
+    } else {
+        Some(quote::quote! {
+            ::core::result::Result::Ok(::core::default::Default::default())
+        })
+    };
+
+    quote::quote! {
+        if #condition {
+            let _ = ::std::io::Write::write_all(&mut ::std::io::stderr(), #marker.as_bytes());
+            return #return_value;
+        }
+    }
+}
+
+/// Whether a function with the given return type returns `()`.
+pub fn returns_unit(return_type: &syn::ReturnType) -> bool {
+    match return_type {
+        syn::ReturnType::Default => true,
+        syn::ReturnType::Type(_, ty) => {
+            matches!(ty.as_ref(), syn::Type::Tuple(tuple) if tuple.elems.is_empty())
+        }
     }
 }
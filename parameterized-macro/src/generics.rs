@@ -0,0 +1,438 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+
+use crate::attribute::{Fn, ParameterKind, ParameterizedList};
+use crate::tests::TestCases;
+
+/// The generic parameters of a test function, and how each of them receives its arguments.
+pub struct GenericParameters<'ctx> {
+    params: Vec<GenericParameter<'ctx>>,
+    lifetimes: Vec<&'ctx syn::Ident>,
+    /// Whether the generic arguments must be given explicitly, using a turbofish
+    explicit: bool,
+    /// The type of the `impl` block, if the test function is a method
+    self_ty: Option<&'ctx syn::Type>,
+}
+
+struct GenericParameter<'ctx> {
+    ident: &'ctx syn::Ident,
+    is_type: bool,
+    source: ArgumentSource,
+}
+
+enum ArgumentSource {
+    /// From a `T = type { .. }` or `N = const { .. }` list
+    List,
+    /// From the default of the generic parameter, e.g. `T = u8`
+    Default(TokenStream),
+    /// Inferred by the compiler, from the arguments of the test case
+    Inferred,
+}
+
+impl<'ctx> GenericParameters<'ctx> {
+    pub fn new(f: &'ctx Fn, lists: &'ctx ParameterizedList) -> syn::Result<Self> {
+        let generics = &f.item_fn.sig.generics;
+
+        for list in &lists.args {
+            let (found, expected) = match list.kind {
+                ParameterKind::Value => continue,
+                ParameterKind::Type => (
+                    generics.type_params().any(|param| param.ident == list.id),
+                    "types",
+                ),
+                ParameterKind::Const => (
+                    generics.const_params().any(|param| param.ident == list.id),
+                    "const arguments",
+                ),
+            };
+
+            if !found {
+                return Err(syn::Error::new(
+                    list.id.span(),
+                    format!(
+                        "parameterized-macro: error: Found a list of {} for '{}', but the test function has no such generic parameter",
+                        expected, list.id
+                    ),
+                ));
+            }
+        }
+
+        let parameter_types = f
+            .item_fn
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                syn::FnArg::Typed(pat_type) => Some(pat_type.ty.to_token_stream()),
+                syn::FnArg::Receiver(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut params = Vec::new();
+        let mut lifetimes = Vec::new();
+
+        for param in &generics.params {
+            let (ident, is_type, default) = match param {
+                syn::GenericParam::Lifetime(param) => {
+                    lifetimes.push(&param.lifetime.ident);
+                    continue;
+                }
+                syn::GenericParam::Type(param) => (
+                    &param.ident,
+                    true,
+                    param.default.as_ref().map(ToTokens::to_token_stream),
+                ),
+                syn::GenericParam::Const(param) => (
+                    &param.ident,
+                    false,
+                    param.default.as_ref().map(ToTokens::to_token_stream),
+                ),
+            };
+
+            let has_list = lists.args.iter().any(|list| {
+                list.id == *ident
+                    && matches!(
+                        (&list.kind, is_type),
+                        (ParameterKind::Type, true) | (ParameterKind::Const, false)
+                    )
+            });
+
+            let source = if has_list {
+                ArgumentSource::List
+            } else if let Some(default) = default {
+                ArgumentSource::Default(default)
+            } else if parameter_types.iter().any(|ty| mentions(ty, ident)) {
+                ArgumentSource::Inferred
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "parameterized-macro: error: Unable to infer generic parameter '{0}' from the parameters of the test function; \
+                            add `{0} = {1} {{ .. }}` to provide one for each test case",
+                        ident,
+                        if is_type { "type" } else { "const" },
+                    ),
+                ));
+            };
+
+            params.push(GenericParameter {
+                ident,
+                is_type,
+                source,
+            });
+        }
+
+        let explicit = params
+            .iter()
+            .any(|param| !matches!(param.source, ArgumentSource::Inferred));
+
+        // `_` can't be used for const arguments in a turbofish
+        if explicit {
+            if let Some(param) = params
+                .iter()
+                .find(|param| !param.is_type && matches!(param.source, ArgumentSource::Inferred))
+            {
+                return Err(syn::Error::new(
+                    param.ident.span(),
+                    format!(
+                        "parameterized-macro: error: Unable to infer const generic parameter '{0}', since other generic arguments are given explicitly; \
+                            add `{0} = const {{ .. }}` to provide one for each test case",
+                        param.ident,
+                    ),
+                ));
+            }
+        }
+
+        Ok(Self {
+            params,
+            lifetimes,
+            explicit,
+            self_ty: f.method.as_ref().map(|method| &method.self_ty),
+        })
+    }
+
+    /// The generic arguments of the `ith` test case, in the order of the generic parameters, or
+    /// `None` if they can all be inferred.
+    pub fn arguments(&self, test_cases: &TestCases, ith: usize) -> Option<Vec<TokenStream>> {
+        if !self.explicit {
+            return None;
+        }
+
+        let arguments = self
+            .params
+            .iter()
+            .map(|param| match &param.source {
+                ArgumentSource::List => {
+                    test_cases.get_argument(param.ident, ith).generic_argument()
+                }
+                ArgumentSource::Default(default) => default.clone(),
+                ArgumentSource::Inferred => quote::quote!(_),
+            })
+            .collect();
+
+        Some(arguments)
+    }
+
+    /// A substitution which replaces the generic parameters with the arguments of the `ith` test case.
+    pub fn substitution(&self, test_cases: &TestCases, ith: usize) -> Substitution<'ctx> {
+        let mut substitution = Substitution {
+            types: Vec::new(),
+            consts: Vec::new(),
+            inferred_consts: Vec::new(),
+            lifetimes: self.lifetimes.clone(),
+            self_ty: self.self_ty,
+            incomplete: false,
+        };
+
+        for param in &self.params {
+            let replacement = match &param.source {
+                ArgumentSource::List => {
+                    test_cases.get_argument(param.ident, ith).generic_argument()
+                }
+                ArgumentSource::Default(default) => default.clone(),
+                ArgumentSource::Inferred if param.is_type => quote::quote!(_),
+                ArgumentSource::Inferred => {
+                    substitution.inferred_consts.push(param.ident);
+                    continue;
+                }
+            };
+
+            if param.is_type {
+                substitution.types.push((param.ident, replacement));
+            } else {
+                substitution.consts.push((param.ident, replacement));
+            }
+        }
+
+        substitution
+    }
+
+    /// A copy of the test function, which is defined next to the generated test cases.
+    ///
+    /// Defaults of generic parameters are removed, since they are not allowed on functions, and
+    /// are passed explicitly instead.
+    pub fn item_fn(&self, f: &Fn) -> syn::ItemFn {
+        let mut item_fn = f.item_fn.clone();
+        item_fn.vis = syn::Visibility::Inherited;
+
+        for param in item_fn.sig.generics.params.iter_mut() {
+            match param {
+                syn::GenericParam::Type(param) => {
+                    param.eq_token = None;
+                    param.default = None;
+                }
+                syn::GenericParam::Const(param) => {
+                    param.eq_token = None;
+                    param.default = None;
+                }
+                syn::GenericParam::Lifetime(_) => {}
+            }
+        }
+
+        item_fn
+    }
+}
+
+/// Whether the given tokens contain the identifier `ident`.
+fn mentions(tokens: &TokenStream, ident: &syn::Ident) -> bool {
+    tokens.clone().into_iter().any(|tree| match tree {
+        proc_macro2::TokenTree::Ident(id) => id == *ident,
+        proc_macro2::TokenTree::Group(group) => mentions(&group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// Replaces generic parameters, such as those in the types of the test function's parameters,
+/// with the generic arguments of a test case.
+pub struct Substitution<'ctx> {
+    types: Vec<(&'ctx syn::Ident, TokenStream)>,
+    consts: Vec<(&'ctx syn::Ident, TokenStream)>,
+    /// Const parameters which will be inferred; these can't be written as `_` on stable Rust
+    inferred_consts: Vec<&'ctx syn::Ident>,
+    /// Lifetime parameters, which are replaced by `'_`
+    lifetimes: Vec<&'ctx syn::Ident>,
+    /// The type which replaces `Self`, for parameterized methods
+    self_ty: Option<&'ctx syn::Type>,
+    /// Whether an inferred const parameter was encountered
+    incomplete: bool,
+}
+
+impl Substitution<'_> {
+    pub fn apply<T: Clone>(&mut self, node: &T, visit: fn(&mut Self, &mut T)) -> T {
+        let mut node = node.clone();
+        visit(self, &mut node);
+        node
+    }
+
+    /// The type of a parameter binding, or `None` if the type can't be written down, in which
+    /// case it's left to the compiler to infer it.
+    pub fn binding_type(&mut self, ty: &syn::Type) -> Option<syn::Type> {
+        self.incomplete = false;
+        let ty = self.apply(ty, VisitMut::visit_type_mut);
+
+        if self.incomplete {
+            None
+        } else {
+            Some(ty)
+        }
+    }
+
+    fn is_inferred_const(&self, path: &syn::Path) -> bool {
+        path.get_ident()
+            .map_or(false, |ident| self.inferred_consts.contains(&ident))
+    }
+
+    /// Qualifies a path such as `Self::Output` as `<SelfTy>::Output`, since `Self` doesn't
+    /// exist outside of the `impl` block.
+    fn qualify_self(&self, qself: &mut Option<syn::QSelf>, path: &mut syn::Path) {
+        let self_ty = match self.self_ty {
+            Some(self_ty) if qself.is_none() && path.leading_colon.is_none() => self_ty,
+            _ => return,
+        };
+
+        if path.segments.len() < 2 || path.segments[0].ident != "Self" {
+            return;
+        }
+
+        // `<SelfTy>::Output` is a qualified path with a leading `::`, and without the `Self` segment
+        path.leading_colon = Some(Default::default());
+        path.segments = path.segments.iter().skip(1).cloned().collect();
+        *qself = Some(syn::QSelf {
+            lt_token: Default::default(),
+            ty: Box::new(self_ty.clone()),
+            position: 0,
+            as_token: None,
+            gt_token: Default::default(),
+        });
+    }
+
+    fn lookup<'a>(
+        replacements: &'a [(&syn::Ident, TokenStream)],
+        path: &syn::Path,
+    ) -> Option<&'a TokenStream> {
+        let ident = path.get_ident()?;
+
+        replacements
+            .iter()
+            .find(|(id, _)| *id == ident)
+            .map(|(_, replacement)| replacement)
+    }
+}
+
+impl VisitMut for Substitution<'_> {
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+        if let syn::Type::Path(syn::TypePath { qself: None, path }) = ty {
+            if let Some(replacement) = Self::lookup(&self.types, path) {
+                *ty = parse_quote!(#replacement);
+                return;
+            }
+
+            if let (Some(self_ty), true) = (self.self_ty, path.is_ident("Self")) {
+                *ty = self_ty.clone();
+                return;
+            }
+        }
+
+        if let syn::Type::Path(syn::TypePath { qself, path }) = ty {
+            self.qualify_self(qself, path);
+        }
+
+        visit_mut::visit_type_mut(self, ty)
+    }
+
+    fn visit_generic_argument_mut(&mut self, argument: &mut syn::GenericArgument) {
+        // a const parameter used as a generic argument, e.g. `Buffer<N>`, is parsed as a type
+        if let syn::GenericArgument::Type(syn::Type::Path(syn::TypePath { qself: None, path })) =
+            argument
+        {
+            if let Some(replacement) = Self::lookup(&self.consts, path) {
+                *argument = syn::GenericArgument::Const(parse_quote!(#replacement));
+                return;
+            }
+
+            if self.is_inferred_const(path) {
+                self.incomplete = true;
+            }
+        }
+
+        visit_mut::visit_generic_argument_mut(self, argument)
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        if let syn::Expr::Path(syn::ExprPath {
+            qself: None, path, ..
+        }) = expr
+        {
+            if let Some(replacement) = Self::lookup(&self.consts, path) {
+                *expr = parse_quote!(#replacement);
+                return;
+            }
+
+            if self.is_inferred_const(path) {
+                self.incomplete = true;
+            }
+        }
+
+        if let syn::Expr::Path(syn::ExprPath { qself, path, .. }) = expr {
+            self.qualify_self(qself, path);
+        }
+
+        visit_mut::visit_expr_mut(self, expr)
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        if self.lifetimes.contains(&&lifetime.ident) {
+            lifetime.ident = syn::Ident::new("_", lifetime.ident.span());
+        }
+    }
+}
+
+/// Replaces each `impl Trait` in a type with a new generic type parameter, bound by `Trait`.
+///
+/// A value can't be bound to a variable of an `impl Trait` type, so instead, we pass it
+/// through a function with these generic parameters, which checks that the value implements
+/// the trait, and lets the compiler infer the signature of closures.
+#[derive(Default)]
+pub struct ImplTraitParameters {
+    params: Vec<TokenStream>,
+}
+
+impl ImplTraitParameters {
+    /// Generate an expression which checks that `expr` satisfies the `impl Trait` bounds in `ty`,
+    /// or `None` if `ty` doesn't contain `impl Trait`.
+    pub fn check(ty: &syn::Type, expr: &syn::Expr) -> Option<TokenStream> {
+        let mut parameters = Self::default();
+        let mut ty = ty.clone();
+        parameters.visit_type_mut(&mut ty);
+
+        if parameters.params.is_empty() {
+            return None;
+        }
+
+        let params = &parameters.params;
+
+        Some(quote::quote! {
+            {
+                fn __parameterized_impl_trait<#(#params),*>(value: #ty) -> #ty {
+                    value
+                }
+
+                __parameterized_impl_trait(#expr)
+            }
+        })
+    }
+}
+
+impl VisitMut for ImplTraitParameters {
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+        // nested `impl Trait` types, e.g. `impl Iterator<Item = impl Display>`, are replaced first
+        visit_mut::visit_type_mut(self, ty);
+
+        if let syn::Type::ImplTrait(syn::TypeImplTrait { bounds, .. }) = ty {
+            let ident = quote::format_ident!("__Impl{}", self.params.len());
+            self.params.push(quote::quote!(#ident: #bounds));
+            *ty = parse_quote!(#ident);
+        }
+    }
+}
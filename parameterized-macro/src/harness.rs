@@ -0,0 +1,187 @@
+use proc_macro2::TokenStream;
+
+use crate::attribute::{Fn, MacroAttribute, ParameterizedList};
+use crate::generation::Layout;
+use crate::tests::TestCases;
+
+/// Registration of the test cases with the built-in test runner of the `parameterized` crate
+/// (`parameterized::harness`), for test targets which don't use the default test harness.
+///
+/// Instead of marking each case with `#[test]`, the generated module contains a `CASES`
+/// constant, which lists the test cases together with their arguments. With `flat`, the constant
+/// is prefixed with the name of the test function instead, e.g. `ADD5_CASES`, so test cases
+/// defined within a function body can be run from there.
+///
+/// With `aggregate`, the registered test cases are run by a single `#[test]` instead, which
+/// reports all failing cases together.
+pub struct Harness {
+    ignore: bool,
+    should_panic: TokenStream,
+    aggregate: bool,
+}
+
+impl Harness {
+    pub fn new(argument_lists: &ParameterizedList, f: &Fn) -> syn::Result<Option<Self>> {
+        let option =
+            match (&argument_lists.harness, &argument_lists.aggregate) {
+                (Some(_), Some(aggregate)) => return Err(syn::Error::new(
+                    aggregate.span,
+                    "parameterized-macro: error: `aggregate` can't be used together with `harness`",
+                )),
+                (Some(_), None) => "harness",
+                (None, Some(_)) => "aggregate",
+                (None, None) => return Ok(None),
+            };
+
+        if let Some(asyncness) = f.asyncness() {
+            return Err(syn::Error::new_spanned(
+                asyncness,
+                format!(
+                    "parameterized-macro: error: An `async` test function can't be run by `{}`",
+                    option
+                ),
+            ));
+        }
+
+        let mut ignore = false;
+        let mut should_panic = quote::quote!(No);
+
+        for attr in &f.attrs {
+            let attr = match attr {
+                MacroAttribute::UseTestMacro(meta) => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        format!(
+                            "parameterized-macro: error: A custom test macro can't be used together with `{}`, since the test cases are run by the built-in test runner",
+                            option
+                        ),
+                    ))
+                }
+                MacroAttribute::Unrelated(attr) => attr,
+            };
+
+            if attr.path().is_ident("ignore") {
+                ignore = true;
+            } else if attr.path().is_ident("should_panic") {
+                should_panic = match &attr.meta {
+                    syn::Meta::Path(_) => quote::quote!(Yes),
+                    syn::Meta::NameValue(name_value) => {
+                        let expected = &name_value.value;
+                        quote::quote!(YesWithMessage(#expected))
+                    }
+                    syn::Meta::List(list) => {
+                        let expected = list.parse_args_with(|input: syn::parse::ParseStream| {
+                            let key = input.parse::<syn::Ident>()?;
+                            if key != "expected" {
+                                return Err(syn::Error::new(key.span(), "expected `expected`"));
+                            }
+                            input.parse::<Token![=]>()?;
+                            input.parse::<syn::LitStr>()
+                        })?;
+
+                        quote::quote!(YesWithMessage(#expected))
+                    }
+                };
+            }
+        }
+
+        Ok(Some(Self {
+            ignore,
+            should_panic,
+            aggregate: argument_lists.aggregate.is_some(),
+        }))
+    }
+
+    /// Whether the test cases are run by a single, aggregated `#[test]`, which still requires
+    /// `cfg(test)`.
+    pub fn is_aggregate(&self) -> bool {
+        self.aggregate
+    }
+
+    /// Whether an attribute of the test function is interpreted by the test runner, in which
+    /// case it's not copied to the test cases.
+    pub fn is_test_attribute(attr: &MacroAttribute) -> bool {
+        match attr {
+            MacroAttribute::Unrelated(attr) => {
+                attr.path().is_ident("ignore") || attr.path().is_ident("should_panic")
+            }
+            MacroAttribute::UseTestMacro(_) => true,
+        }
+    }
+
+    /// Generate the `CASES` constant, which registers the given test cases by their index and
+    /// the name of the generated function.
+    pub fn generate_registry(
+        &self,
+        f: &Fn,
+        layout: &Layout,
+        argument_lists: &ParameterizedList,
+        values: &TestCases,
+        cases: &[(usize, syn::Ident)],
+    ) -> TokenStream {
+        let ignore = self.ignore;
+        let should_panic = &self.should_panic;
+
+        let entries = cases.iter().map(|(i, identifier)| {
+            let name = format!("::{}", identifier);
+
+            let parameters = argument_lists.args.iter().map(|list| {
+                let parameter = list.id.to_string();
+                let argument = values.get_argument(&list.id, *i).display();
+                quote::quote!((#parameter, #argument))
+            });
+
+            let mut call = quote::quote!(#identifier());
+            if f.unsafety().is_some() {
+                call = quote::quote!(unsafe { #call });
+            }
+
+            quote::quote! {
+                ::parameterized::harness::Case {
+                    index: #i,
+                    path: ::core::concat!(::core::module_path!(), #name),
+                    parameters: &[#(#parameters),*],
+                    ignore: #ignore,
+                    should_panic: ::parameterized::harness::ShouldPanic::#should_panic,
+                    run: || ::parameterized::harness::Outcome::into_result(#call),
+                }
+            }
+        });
+
+        // without a module, the constant is prefixed with the name of the test function
+        let constant = match layout {
+            Layout::Module(_) => quote::format_ident!("CASES"),
+            Layout::Flat => {
+                quote::format_ident!("{}_CASES", f.item_fn.sig.ident.to_string().to_uppercase())
+            }
+        };
+        let all_cases = layout.item_name(f, "all_cases");
+
+        let cases = quote::quote! {
+            /// The test cases, to be run by `parameterized::harness::main`.
+            #[allow(dead_code)]
+            pub const #constant: &[::parameterized::harness::Case] = &[#(#entries),*];
+        };
+
+        if !self.aggregate {
+            return cases;
+        }
+
+        // an ignored test function is ignored as a whole; its cases all run when it's requested
+        let ignore = if self.ignore {
+            Some(quote::quote!(#[ignore]))
+        } else {
+            None
+        };
+
+        quote::quote! {
+            #cases
+
+            #[test]
+            #ignore
+            fn #all_cases() {
+                ::parameterized::harness::aggregate(#constant);
+            }
+        }
+    }
+}
@@ -4,7 +4,16 @@ extern crate core;
 extern crate proc_macro;
 
 mod attribute;
+mod bench;
+mod const_eval;
+mod constraint;
+mod diagnostics;
+mod expectation;
+mod fixture;
 mod generation;
+mod generics;
+mod harness;
+mod sampling;
 mod tests;
 
 #[proc_macro_attribute]
@@ -15,12 +24,37 @@ pub fn parameterized(
     impl_macro(args, input)
 }
 
+/// Generate a benchmark for each test case, instead of a test, which is timed by the built-in
+/// benchmark runner (`parameterized::bench`).
+#[proc_macro_attribute]
+pub fn parameterized_bench(
+    args: ::proc_macro::TokenStream,
+    input: ::proc_macro::TokenStream,
+) -> ::proc_macro::TokenStream {
+    let mut argument_lists = parse_macro_input!(args as attribute::ParameterizedList);
+    let item = parse_macro_input!(input as attribute::Item);
+    argument_lists.bench = true;
+
+    match item {
+        attribute::Item::Fn(func) => generation::generate(argument_lists, func),
+        attribute::Item::Impl(item_impl) => syn::Error::new_spanned(
+            item_impl.impl_token,
+            "parameterized-macro: error: #[parameterized_bench] can only be used on a function",
+        )
+        .into_compile_error()
+        .into(),
+    }
+}
+
 fn impl_macro(
     args: ::proc_macro::TokenStream,
     input: ::proc_macro::TokenStream,
 ) -> ::proc_macro::TokenStream {
     let argument_lists = parse_macro_input!(args as attribute::ParameterizedList);
-    let func = parse_macro_input!(input as attribute::Fn);
+    let item = parse_macro_input!(input as attribute::Item);
 
-    generation::generate(argument_lists, func)
+    match item {
+        attribute::Item::Fn(func) => generation::generate(argument_lists, func),
+        attribute::Item::Impl(item_impl) => fixture::generate(argument_lists, item_impl),
+    }
 }
@@ -0,0 +1,125 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::attribute::{Fn, Sample, Seed, SeedSource};
+use crate::generation::Layout;
+
+/// A random selection of test cases, made when the macro is expanded.
+pub struct Sampling {
+    amount: usize,
+    seed: u64,
+    /// The environment variable the seed was read from, if any
+    env: Option<String>,
+}
+
+impl Sampling {
+    pub fn new(sample: &Sample, seed: Option<&Seed>) -> syn::Result<Self> {
+        let amount = sample.amount.base10_parse()?;
+
+        let (seed, env) = match seed.map(|seed| &seed.source) {
+            Some(SeedSource::Literal(lit)) => (lit.base10_parse()?, None),
+            Some(SeedSource::Env(name)) => {
+                let seed = match std::env::var(name.value()) {
+                    Ok(value) => value.trim().parse().map_err(|_| {
+                        syn::Error::new_spanned(
+                            name,
+                            format!(
+                                "parameterized-macro: error: Unable to parse the seed '{}' (from environment variable '{}') as an unsigned 64-bit integer",
+                                value,
+                                name.value()
+                            ),
+                        )
+                    })?,
+                    Err(_) => random_seed(),
+                };
+
+                (seed, Some(name.value()))
+            }
+            None => (random_seed(), None),
+        };
+
+        Ok(Self { amount, seed, env })
+    }
+
+    /// Select `amount` of the given cases, keeping them in their original order.
+    pub fn select<T>(&self, cases: Vec<T>) -> Vec<T> {
+        if self.amount >= cases.len() {
+            return cases;
+        }
+
+        // partial Fisher-Yates shuffle: the first `amount` indices form the sample
+        let mut indices = (0..cases.len()).collect::<Vec<_>>();
+        let mut rng = SplitMix64(self.seed);
+        for i in 0..self.amount {
+            let remaining = (indices.len() - i) as u64;
+            let j = i + (rng.next() % remaining) as usize;
+            indices.swap(i, j);
+        }
+
+        indices.truncate(self.amount);
+        indices.sort_unstable();
+
+        cases
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| indices.binary_search(i).is_ok())
+            .map(|(_, case)| case)
+            .collect()
+    }
+
+    /// Items which record the seed in the generated module.
+    pub fn generate_items(
+        &self,
+        layout: &Layout,
+        f: &Fn,
+        total: usize,
+    ) -> proc_macro2::TokenStream {
+        let seed = self.seed;
+        let doc = format!(
+            " Up to {} of {} test cases were sampled using seed `{}`.\n\n Use `seed = {}` to reproduce this selection.",
+            self.amount, total, seed, seed,
+        );
+
+        // reading the variable with `option_env!` lets the compiler know that this crate must be
+        // rebuilt (and the macro re-expanded) when the variable changes
+        let track_env = self.env.as_ref().map(|name| {
+            quote::quote! {
+                const _: ::core::option::Option<&str> = ::core::option_env!(#name);
+            }
+        });
+
+        // without a module, the constant is prefixed with the name of the test function
+        let constant = match layout {
+            Layout::Module(_) => quote::format_ident!("PARAMETERIZED_SEED"),
+            Layout::Flat => quote::format_ident!(
+                "{}_PARAMETERIZED_SEED",
+                f.item_fn.sig.ident.to_string().to_uppercase()
+            ),
+        };
+
+        quote::quote! {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            pub const #constant: u64 = #seed;
+
+            #track_env
+        }
+    }
+}
+
+fn random_seed() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// The SplitMix64 generator; small, and good enough to pick test cases.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
@@ -1,10 +1,199 @@
 use indexmap::IndexMap;
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
 use std::fmt::Formatter;
 
 type FnvIndexMap<K, V> = IndexMap<K, V, fnv::FnvBuildHasher>;
 
+/// A single argument of a test case.
+#[derive(Clone, Copy)]
+pub enum Argument<'node> {
+    /// A value, given in a `id = { .. }` list
+    Value(&'node syn::Expr),
+    /// A type, given in a `id = type { .. }` list
+    Type(&'node syn::Type),
+    /// A const generic argument, given in a `id = const { .. }` list
+    Const(&'node syn::Expr),
+}
+
+impl Argument<'_> {
+    /// The argument, in a form which can be used as a generic argument.
+    pub fn generic_argument(&self) -> TokenStream {
+        match self {
+            Argument::Type(ty) => ty.to_token_stream(),
+            Argument::Value(expr) | Argument::Const(expr) => match expr {
+                syn::Expr::Lit(_) | syn::Expr::Block(_) => expr.to_token_stream(),
+                _ => quote::quote!({ #expr }),
+            },
+        }
+    }
+
+    /// The argument as it would usually be written, e.g. `u16::MAX` rather than `u16 :: MAX`,
+    /// for use in messages and reports.
+    pub fn display(&self) -> String {
+        let mut printer = Printer {
+            out: String::new(),
+            in_type: matches!(self, Argument::Type(_)),
+        };
+        printer.write(self.to_token_stream());
+        printer.out
+    }
+}
+
+/// A pattern or path as it would usually be written, for use in messages and reports.
+pub fn display(tokens: &impl ToTokens) -> String {
+    let mut printer = Printer {
+        out: String::new(),
+        in_type: false,
+    };
+    printer.write(tokens.to_token_stream());
+    printer.out
+}
+
+/// Writes tokens with conventional spacing. Since a span can't be joined on stable Rust, the
+/// source text of an expression isn't available, so it's approximated instead.
+struct Printer {
+    out: String,
+    /// Whether `<` and `>` are always generic brackets, rather than comparisons
+    in_type: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Last {
+    Start,
+    /// An identifier, literal or group, after which an operator is binary
+    Operand,
+    /// An operator, which is directly followed by its operand
+    Operator,
+    /// A `,`, `;` or `:`, which is followed by a space
+    Separator,
+}
+
+const BINARY_OPERATORS: &[&str] = &[
+    "=", "==", "!=", "<", ">", "<=", ">=", "+", "-", "*", "/", "%", "&", "|", "^", "&&", "||",
+    "<<", ">>", "+=", "-=", "*=", "/=", "=>", "->",
+];
+
+impl Printer {
+    fn write(&mut self, tokens: TokenStream) {
+        let mut last = Last::Start;
+        let mut angle_depth = 0usize;
+        let mut in_closure_params = false;
+        let mut trees = tokens.into_iter().peekable();
+
+        while let Some(tree) = trees.next() {
+            match tree {
+                TokenTree::Ident(_) | TokenTree::Literal(_) => {
+                    if matches!(last, Last::Operand | Last::Separator) {
+                        self.out.push(' ');
+                    }
+                    self.out.push_str(&tree.to_string());
+                    // the parameters of a `move` closure are preceded by a space
+                    last = if matches!(&tree, TokenTree::Ident(ident) if ident == "move") {
+                        Last::Separator
+                    } else {
+                        Last::Operand
+                    };
+                }
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace if group.stream().is_empty() => ("{", "}"),
+                        Delimiter::Brace => ("{ ", " }"),
+                        Delimiter::None => ("", ""),
+                    };
+
+                    let is_brace = group.delimiter() == Delimiter::Brace;
+                    if last == Last::Separator || (is_brace && last == Last::Operand) {
+                        self.out.push(' ');
+                    }
+
+                    self.out.push_str(open);
+                    self.write(group.stream());
+                    self.out.push_str(close);
+                    last = Last::Operand;
+                }
+                TokenTree::Punct(punct) => {
+                    let mut op = punct.as_char().to_string();
+                    let mut spacing = punct.spacing();
+                    while spacing == Spacing::Joint && op != "'" {
+                        match trees.peek() {
+                            Some(TokenTree::Punct(next)) => {
+                                op.push(next.as_char());
+                                spacing = next.spacing();
+                                trees.next();
+                            }
+                            _ => break,
+                        }
+                    }
+
+                    let opens_generics =
+                        op == "<" && (self.in_type || angle_depth > 0 || self.out.ends_with("::"));
+                    let closes_generics = angle_depth > 0 && op.chars().all(|c| c == '>');
+
+                    last = if opens_generics {
+                        angle_depth += 1;
+                        self.out.push_str(&op);
+                        Last::Operator
+                    } else if closes_generics {
+                        angle_depth = angle_depth.saturating_sub(op.len());
+                        self.out.push_str(&op);
+                        Last::Operand
+                    } else if op == "," || op == ";" || op == ":" {
+                        self.out.push_str(&op);
+                        Last::Separator
+                    } else if op == "|" && in_closure_params {
+                        in_closure_params = false;
+                        self.out.push_str("| ");
+                        Last::Operator
+                    } else if op == "|" && last != Last::Operand {
+                        in_closure_params = true;
+                        self.push_prefix(last, &op)
+                    } else if op == "||" && last != Last::Operand {
+                        self.push_prefix(last, "|| ")
+                    } else if last == Last::Operand && BINARY_OPERATORS.contains(&op.as_str()) {
+                        self.out.push_str(&format!(" {} ", op));
+                        Last::Operator
+                    } else if op == "->" {
+                        // also the return type of a closure, after its parameters
+                        if !self.out.ends_with(' ') {
+                            self.out.push(' ');
+                        }
+                        self.out.push_str("-> ");
+                        Last::Operator
+                    } else if op == "?" {
+                        self.out.push_str(&op);
+                        Last::Operand
+                    } else {
+                        // paths, ranges, unary operators and macro invocations
+                        self.push_prefix(last, &op)
+                    };
+                }
+            }
+        }
+    }
+
+    fn push_prefix(&mut self, last: Last, op: &str) -> Last {
+        if last == Last::Separator {
+            self.out.push(' ');
+        }
+        self.out.push_str(op);
+        Last::Operator
+    }
+}
+
+impl ToTokens for Argument<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Argument::Value(expr) | Argument::Const(expr) => expr.to_tokens(tokens),
+            Argument::Type(ty) => ty.to_tokens(tokens),
+        }
+    }
+}
+
 pub struct TestCases<'node> {
-    map: FnvIndexMap<&'node syn::Ident, Vec<&'node syn::Expr>>,
+    map: FnvIndexMap<&'node syn::Ident, Vec<Argument<'node>>>,
     amount_of_test_cases: Option<usize>,
 }
 
@@ -28,7 +217,7 @@ impl<'node> TestCases<'node> {
         }
     }
 
-    pub fn insert(&mut self, id: &'node syn::Ident, exprs: Vec<&'node syn::Expr>) {
+    pub fn insert(&mut self, id: &'node syn::Ident, exprs: Vec<Argument<'node>>) {
         let expressions = exprs.len();
 
         //
@@ -62,7 +251,17 @@ impl<'node> TestCases<'node> {
         }
     }
 
-    pub fn get(&self, id: &syn::Ident, ith: usize) -> &syn::Expr {
+    pub fn get(&self, id: &syn::Ident, ith: usize) -> &'node syn::Expr {
+        match self.get_argument(id, ith) {
+            Argument::Value(expr) => expr,
+            Argument::Type(_) | Argument::Const(_) => panic!(
+                "parameterized-macro: error: Expected values for parameter '{}', but got a list of generic arguments",
+                id
+            ),
+        }
+    }
+
+    pub fn get_argument(&self, id: &syn::Ident, ith: usize) -> Argument<'node> {
         if let Some(exprs) = self.map.get(id) {
             exprs[ith]
         } else {
@@ -89,3 +288,85 @@ impl<'node> TestCases<'node> {
         }
     }
 }
+
+#[cfg(test)]
+mod display {
+    use super::*;
+
+    fn expr(source: &str) -> String {
+        Argument::Value(&syn::parse_str(source).unwrap()).display()
+    }
+
+    fn ty(source: &str) -> String {
+        Argument::Type(&syn::parse_str(source).unwrap()).display()
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(expr("u16 :: MAX"), "u16::MAX");
+        assert_eq!(expr("std::f64::consts::PI"), "std::f64::consts::PI");
+        assert_eq!(expr("Mode::Fast"), "Mode::Fast");
+        assert_eq!(expr("x.len()"), "x.len()");
+    }
+
+    #[test]
+    fn generics() {
+        assert_eq!(expr("Vec::<u8>::new()"), "Vec::<u8>::new()");
+        assert_eq!(
+            expr("HashMap::<String, Vec<u8>>::new()"),
+            "HashMap::<String, Vec<u8>>::new()"
+        );
+        assert_eq!(ty("Vec<Option<u8>>"), "Vec<Option<u8>>");
+        assert_eq!(ty("HashMap<String, Vec<u8>>"), "HashMap<String, Vec<u8>>");
+        assert_eq!(ty("dyn Iterator<Item = u8>"), "dyn Iterator<Item = u8>");
+        assert_eq!(ty("&'static str"), "&'static str");
+        assert_eq!(ty("fn(u8) -> u8"), "fn(u8) -> u8");
+    }
+
+    #[test]
+    fn closures() {
+        assert_eq!(expr("|x| x + 1"), "|x| x + 1");
+        assert_eq!(expr("|| 1"), "|| 1");
+        assert_eq!(expr("move |a, b| a < b"), "move |a, b| a < b");
+        assert_eq!(expr("|a: u8| -> u8 { a }"), "|a: u8| -> u8 { a }");
+    }
+
+    #[test]
+    fn comparison_operators() {
+        assert_eq!(expr("a < b"), "a < b");
+        assert_eq!(expr("a >= b && c"), "a >= b && c");
+        assert_eq!(expr("a << 2 > b"), "a << 2 > b");
+        assert_eq!(expr("a::<u8>() < 1"), "a::<u8>() < 1");
+    }
+
+    #[test]
+    fn macro_calls() {
+        assert_eq!(expr("vec![1, 2, 3]"), "vec![1, 2, 3]");
+        assert_eq!(expr("format!(\"{}\", 1)"), "format!(\"{}\", 1)");
+    }
+
+    #[test]
+    fn negative_literals() {
+        assert_eq!(expr("-1"), "-1");
+        assert_eq!(expr("-1.5"), "-1.5");
+        assert_eq!(expr("(-1, -2)"), "(-1, -2)");
+        assert_eq!(expr("a - -1"), "a - -1");
+        assert_eq!(expr("Foo { a: 1, b: -2 }"), "Foo { a: 1, b: -2 }");
+    }
+
+    #[test]
+    fn other_expressions() {
+        assert_eq!(expr("&mut x"), "&mut x");
+        assert_eq!(expr("0..=5"), "0..=5");
+        assert_eq!(expr("Some(x?)"), "Some(x?)");
+        assert_eq!(expr("[0; 4]"), "[0; 4]");
+        assert_eq!(expr("x as u8"), "x as u8");
+    }
+
+    #[test]
+    fn patterns() {
+        let pattern =
+            syn::parse::Parser::parse_str(syn::Pat::parse_multi, "Err(_) | None").unwrap();
+        assert_eq!(display(&pattern), "Err(_) | None");
+    }
+}
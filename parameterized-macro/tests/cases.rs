@@ -23,6 +23,16 @@ fn individual_cases() {
     t.pass("tests/ok/20_empty.rs");
     t.pass("tests/ok/21_custom_test_attribute.rs");
     t.pass("tests/ok/22_custom_test_attribute_complex_meta.rs");
+    t.pass("tests/ok/23_constraints.rs");
+    t.pass("tests/ok/24_sample.rs");
+    t.pass("tests/ok/25_generics.rs");
+    t.pass("tests/ok/26_generic_inference.rs");
+    t.pass("tests/ok/27_impl_trait.rs");
+    t.pass("tests/ok/28_fixture_method.rs");
+    t.pass("tests/ok/29_flat.rs");
+    t.pass("tests/ok/30_module_name.rs");
+    t.pass("tests/ok/31_async_without_runtime.rs");
+    t.pass("tests/ok/32_multiple_custom_test_attributes.rs");
 
     t.compile_fail("tests/fail/id_already_defined.rs");
     t.compile_fail("tests/fail/inequal_amount_of_arg.rs");
@@ -30,7 +40,31 @@ fn individual_cases() {
     t.compile_fail("tests/fail/input_param_order_in_err_message.rs");
     t.compile_fail("tests/fail/not_a_fn.rs");
     t.compile_fail("tests/fail/on_visibility.rs");
-    t.compile_fail("tests/fail/multiple_custom_test_attributes.rs");
+    t.compile_fail("tests/fail/where_unknown_parameter.rs");
+    t.compile_fail("tests/fail/exclude_amount_of_values.rs");
+    t.compile_fail("tests/fail/exclude_reserved_key.rs");
+    t.compile_fail("tests/fail/seed_without_sample.rs");
+    t.compile_fail("tests/fail/generic_missing_arguments.rs");
+    t.compile_fail("tests/fail/generic_unknown_parameter.rs");
+    t.compile_fail("tests/fail/generic_inferred_const_with_explicit_type.rs");
+    t.compile_fail("tests/fail/method_without_fixture.rs");
+    t.compile_fail("tests/fail/impl_with_module.rs");
+    t.compile_fail("tests/fail/flat_with_module.rs");
+    t.compile_fail("tests/fail/harness_async.rs");
+    t.compile_fail("tests/fail/aggregate_with_harness.rs");
+    t.compile_fail("tests/fail/case_index_with_arguments.rs");
+    t.compile_fail("tests/fail/returns_unit.rs");
+    t.compile_fail("tests/fail/returns_amount_of_values.rs");
+    t.compile_fail("tests/fail/expect_with_returns.rs");
+    t.compile_fail("tests/fail/oracle_with_returns.rs");
+    t.compile_fail("tests/fail/snapshot_unknown_format.rs");
+    t.compile_fail("tests/fail/bench_with_harness.rs");
+    t.compile_fail("tests/fail/const_eval_failing_case.rs");
+    t.compile_fail("tests/fail/const_eval_not_const.rs");
+    t.compile_fail("tests/fail/compile_fail_with_snapshot.rs");
+    t.compile_fail("tests/fail/runtime_unknown_option.rs");
+    t.compile_fail("tests/fail/empty_custom_test_attribute.rs");
+    t.compile_fail("tests/fail/test_macro_with_parameterized_macro.rs");
 
     #[cfg(not(feature = "__unstable_square-brackets-old-error-message"))]
     t.compile_fail("tests/fail/square_brackets.rs");
@@ -0,0 +1,6 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2 }, harness, aggregate)]
+fn my_test(v: i32) {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use parameterized_macro::parameterized_bench;
+
+#[parameterized_bench(v = { 1, 2 }, harness)]
+fn my_bench(v: i32) -> i32 {
+    v * 2
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2 }, i = { 0, 1 })]
+fn my_test(v: i32, #[case_index] i: usize) {}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(source = { "pub const X: u8 = 256;" }, snapshot, compile_fail)]
+fn my_test(source: &str) -> &str {
+    source
+}
+
+fn main() {}
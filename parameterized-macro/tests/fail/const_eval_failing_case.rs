@@ -0,0 +1,8 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2, 3 }, const_eval)]
+const fn my_test(v: u8) {
+    assert!(v < 3, "v is too large");
+}
+
+fn main() {}
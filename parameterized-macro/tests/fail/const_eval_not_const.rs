@@ -0,0 +1,8 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2 }, const_eval)]
+fn my_test(v: u8) {
+    assert!(v < 3);
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2 })]
+#[parameterized_macro()]
+fn my_test(v: u32) {
+    assert!(v > 0);
+}
+
+fn main() {}
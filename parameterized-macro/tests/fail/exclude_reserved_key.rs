@@ -0,0 +1,6 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2, 3 }, exclude = { 1, 2, 3 })]
+fn my_test(v: i32, exclude: i32) {}
+
+fn main() {}
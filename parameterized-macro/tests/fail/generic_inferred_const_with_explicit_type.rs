@@ -0,0 +1,6 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(T = type { u8, u16 }, bytes = { [1], [1, 2] })]
+fn my_test<T, const N: usize>(bytes: [u8; N]) {}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(T = type { u8, u16 }, v = { 1, 2 })]
+fn my_test<T, const N: usize>(v: T) {}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(U = type { u8, u16 }, v = { 1, 2 })]
+fn my_test(v: u32) {}
+
+fn main() {}
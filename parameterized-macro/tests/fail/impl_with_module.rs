@@ -0,0 +1,12 @@
+use parameterized_macro::parameterized;
+
+#[derive(Default)]
+struct TestCtx;
+
+#[parameterized(module = named)]
+impl TestCtx {
+    #[parameterized(input = { 1, 2 })]
+    fn check(&self, input: u8) {}
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use parameterized_macro::parameterized;
+
+struct TestCtx;
+
+impl TestCtx {
+    #[parameterized(input = { 1, 2 })]
+    fn check(&self, input: u8) {}
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use parameterized_macro::parameterized;
+
+fn reference(v: i32) -> i32 {
+    v
+}
+
+#[parameterized(v = { 1, 2 }, returns = { 1, 2 }, oracle = reference)]
+fn my_test(v: i32) -> i32 {
+    v
+}
+
+fn main() {}
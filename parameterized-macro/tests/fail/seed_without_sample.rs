@@ -0,0 +1,6 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2, 3 }, seed = 7)]
+fn my_test(v: i32) {}
+
+fn main() {}
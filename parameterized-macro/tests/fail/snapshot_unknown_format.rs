@@ -0,0 +1,8 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2 }, snapshot = json)]
+fn my_test(v: i32) -> i32 {
+    v
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2 }, test_macro = tokio::test)]
+#[parameterized_macro(tokio::test)]
+async fn my_test(v: u32) {
+    assert!(v > 0);
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2, 3 }, where = |w| w > 1)]
+fn my_test(v: i32) {}
+
+fn main() {}
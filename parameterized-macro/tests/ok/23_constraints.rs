@@ -0,0 +1,19 @@
+use parameterized_macro::parameterized;
+
+#[derive(PartialEq)]
+enum Mode {
+    Fast,
+    Slow,
+}
+
+const THREADS: usize = 2;
+
+#[parameterized(
+    mode = { Mode::Fast, Mode::Slow, Mode::Fast },
+    threads = { 0, 0, THREADS },
+    where = |mode, threads| mode != Mode::Fast || threads > 0,
+    exclude = { (Mode::Slow, _) },
+)]
+fn my_test(mode: Mode, threads: usize) {}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(
+    v = { 1, 2, 3, 4, 5, 6 },
+    w = { 1, 2, 3, 4, 5, 6 },
+    sample = 2,
+    seed = env("PARAMETERIZED_SEED"),
+)]
+fn my_test(v: u32, w: u32) {}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(T = type { u8, u16, u64 }, N = const { 1, 4, 16 }, input = { [1; 1], [2; 4], [3; 16] })]
+fn my_test<T: Copy + Default, const N: usize>(input: [T; N]) {}
+
+fn main() {}
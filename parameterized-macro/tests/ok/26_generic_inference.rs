@@ -0,0 +1,10 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(input = { "a", "b" }, bytes = { [1], [1, 2] }, value = { 1u8, 2u16 })]
+fn my_test<'a, T, const N: usize>(input: &'a str, bytes: [u8; N], value: T)
+where
+    T: Into<u32>,
+{
+}
+
+fn main() {}
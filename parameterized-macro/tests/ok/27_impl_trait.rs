@@ -0,0 +1,8 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(f = { |x| x + 1, |x| x * 2 }, expected = { 4, 6 })]
+fn my_test(f: impl Fn(u8) -> u8, expected: u8) {
+    assert_eq!(f(3), expected);
+}
+
+fn main() {}
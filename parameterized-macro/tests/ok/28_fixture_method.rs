@@ -0,0 +1,16 @@
+use parameterized_macro::parameterized;
+
+#[derive(Default)]
+struct TestCtx {
+    offset: u8,
+}
+
+#[parameterized]
+impl TestCtx {
+    #[parameterized(input = { 1, 2 }, expected = { 1, 2 })]
+    fn check(&self, input: u8, expected: u8) {
+        assert_eq!(self.offset + input, expected);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use parameterized_macro::parameterized;
+
+fn main() {
+    struct Local(u8);
+
+    #[parameterized(value = { Local(1), Local(2) }, flat)]
+    fn my_test(value: Local) {
+        assert!(value.0 > 0);
+    }
+}
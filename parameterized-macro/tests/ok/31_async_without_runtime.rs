@@ -0,0 +1,8 @@
+use parameterized_macro::parameterized;
+
+#[parameterized(v = { 1, 2 })]
+async fn my_test(v: i32) {
+    assert!(v > 0);
+}
+
+fn main() {}
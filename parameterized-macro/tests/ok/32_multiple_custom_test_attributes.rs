@@ -10,4 +10,10 @@ async fn my_test(v: u32, w: u32) {
     assert!(true);
 }
 
+#[parameterized(v = { 1, 2 })]
+#[parameterized_macro(macro1, macro2(option = "value"))]
+async fn my_list_test(v: u32) {
+    assert!(v > 0);
+}
+
 fn main() {}
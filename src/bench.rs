@@ -0,0 +1,285 @@
+//! A small timing harness for the benchmarks generated by `#[parameterized_bench]`, for bench
+//! targets with `harness = false`.
+//!
+//! Each benchmark is a test case of the attribute; its arguments are constructed outside of the
+//! measured time. The module generated for a benchmarked function contains a `BENCHES` constant,
+//! which registers its benchmarks with [`main`]:
+//!
+//! ```rust,ignore
+//! // benches/parse.rs, with `[[bench]] name = "parse", harness = false` in Cargo.toml
+//! use parameterized::parameterized_bench;
+//!
+//! #[parameterized_bench(input = { "1", "65535" })]
+//! fn parse(input: &str) -> u16 {
+//!     input.parse().unwrap()
+//! }
+//!
+//! fn main() {
+//!     parameterized::bench::main(&[parse::BENCHES]);
+//! }
+//! ```
+//!
+//! When run by `cargo bench`, each benchmark reports the mean, minimum and maximum time of a
+//! single iteration over its samples. When run by `cargo test`, each benchmark is only run once,
+//! to check that it doesn't fail.
+//!
+//! The runner accepts benchmark name filters, `--exact`, `--skip <filter>` and `--list`.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::harness::{catch_panic, without_crate_name, Arguments};
+
+/// The measured time of a single sample, i.e. of a batch of iterations.
+const SAMPLE_TIME: Duration = Duration::from_millis(2);
+/// The maximum amount of samples of a benchmark.
+const SAMPLES: usize = 50;
+/// The time after which a benchmark stops taking samples, if it took at least `MIN_SAMPLES`.
+const MEASUREMENT_TIME: Duration = Duration::from_secs(2);
+const MIN_SAMPLES: usize = 5;
+/// The maximum amount of iterations of a batch, whose inputs are created before it is timed,
+/// and whose outputs are dropped after it; a sample may consist of several batches.
+const BATCH_SIZE: usize = 1024;
+/// The maximum size of the inputs and outputs of a batch, in bytes, not counting the memory
+/// which they own.
+const BATCH_MEMORY: usize = 1 << 20;
+
+/// A single benchmark, as registered by the code generated for `#[parameterized_bench]`.
+pub struct Bench {
+    /// The index of the test case, i.e. its row in the parameter lists
+    pub index: usize,
+    /// The module path of the benchmark, including the crate name, e.g. `parse::parse::case_0`
+    pub path: &'static str,
+    /// The parameters of the test case, with their arguments as written
+    pub parameters: &'static [(&'static str, &'static str)],
+    pub run: fn(&mut Bencher),
+}
+
+impl Bench {
+    /// The name of the benchmark, without the crate name.
+    pub fn name(&self) -> &'static str {
+        without_crate_name(self.path)
+    }
+}
+
+/// Times the iterations of a benchmark.
+pub struct Bencher {
+    measure: bool,
+    stats: Option<Stats>,
+}
+
+impl Bencher {
+    /// Time `routine`, with an input which is created by `setup` for each iteration; the time
+    /// spent in `setup`, and dropping the output of `routine`, isn't measured.
+    pub fn iter_with_setup<I, O>(
+        &mut self,
+        mut setup: impl FnMut() -> I,
+        mut routine: impl FnMut(I) -> O,
+    ) {
+        if !self.measure {
+            std::hint::black_box(routine(setup()));
+            return;
+        }
+
+        let batch_size = (BATCH_MEMORY / std::mem::size_of::<(I, O)>().max(1)).clamp(1, BATCH_SIZE);
+
+        let mut sample = |iterations: usize| {
+            let mut elapsed = Duration::ZERO;
+            let mut remaining = iterations;
+
+            while remaining > 0 {
+                let batch = remaining.min(batch_size);
+                remaining -= batch;
+
+                let inputs = (0..batch).map(|_| setup()).collect::<Vec<_>>();
+                let mut outputs = Vec::with_capacity(batch);
+
+                let started = Instant::now();
+                for input in inputs {
+                    outputs.push(std::hint::black_box(routine(std::hint::black_box(input))));
+                }
+                elapsed += started.elapsed();
+            }
+
+            elapsed
+        };
+
+        // the amount of iterations per sample is doubled, until a sample is long enough to be
+        // timed accurately
+        let mut iterations = 1;
+        while iterations < 1 << 20 && sample(iterations) < SAMPLE_TIME {
+            iterations *= 2;
+        }
+
+        let started = Instant::now();
+        let mut samples = Vec::with_capacity(SAMPLES);
+        while samples.len() < SAMPLES
+            && (samples.len() < MIN_SAMPLES || started.elapsed() < MEASUREMENT_TIME)
+        {
+            samples.push(sample(iterations));
+        }
+
+        self.stats = Some(Stats::new(&samples, iterations));
+    }
+
+    /// Time `routine`.
+    pub fn iter<O>(&mut self, mut routine: impl FnMut() -> O) {
+        self.iter_with_setup(|| (), |()| routine());
+    }
+}
+
+/// The time of the samples of a benchmark, which each time the same amount of iterations.
+#[derive(Clone, Copy, Debug)]
+struct Stats {
+    mean: Duration,
+    min: Duration,
+    max: Duration,
+    samples: usize,
+    iterations: usize,
+}
+
+impl Stats {
+    fn new(samples: &[Duration], iterations: usize) -> Self {
+        let total = samples.iter().sum::<Duration>();
+
+        Self {
+            mean: total / samples.len().max(1) as u32,
+            min: samples.iter().copied().min().unwrap_or_default(),
+            max: samples.iter().copied().max().unwrap_or_default(),
+            samples: samples.len(),
+            iterations,
+        }
+    }
+}
+
+impl Stats {
+    /// The time of a single iteration, within a sample which took `sample`.
+    fn per_iteration(&self, sample: Duration) -> Time {
+        Time(sample.as_secs_f64() * 1e9 / self.iterations as f64)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mean {}, min {}, max {} ({} samples of {} iterations)",
+            self.per_iteration(self.mean),
+            self.per_iteration(self.min),
+            self.per_iteration(self.max),
+            self.samples,
+            self.iterations
+        )
+    }
+}
+
+/// A duration in nanoseconds, which is shown in the most suitable unit.
+struct Time(f64);
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.0;
+
+        match nanos {
+            _ if nanos < 1e3 => write!(f, "{:.2} ns", nanos),
+            _ if nanos < 1e6 => write!(f, "{:.2} µs", nanos / 1e3),
+            _ if nanos < 1e9 => write!(f, "{:.2} ms", nanos / 1e6),
+            _ => write!(f, "{:.2} s", nanos / 1e9),
+        }
+    }
+}
+
+/// Run the given benchmarks with the arguments of the bench binary, and exit the process with a
+/// non-zero exit code if any of them failed.
+pub fn main(benches: &[&'static [Bench]]) -> ! {
+    let arguments = match Arguments::from_env() {
+        Ok(arguments) => arguments,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(101);
+        }
+    };
+
+    match run(&arguments, benches, &mut io::stdout()) {
+        Ok(true) => std::process::exit(0),
+        Ok(false) => std::process::exit(101),
+        Err(err) => {
+            eprintln!("error: unable to write the benchmark report: {}", err);
+            std::process::exit(101);
+        }
+    }
+}
+
+/// Run the given benchmarks, and write the report to `out`. The benchmarks are only measured
+/// if `arguments.bench` is set, and are run once otherwise. Returns whether none of the
+/// benchmarks failed.
+pub fn run(
+    arguments: &Arguments,
+    benches: &[&'static [Bench]],
+    out: &mut dyn Write,
+) -> io::Result<bool> {
+    let selected = benches
+        .iter()
+        .flat_map(|benches| benches.iter())
+        .filter(|bench| arguments.is_selected(bench.name()))
+        .collect::<Vec<_>>();
+
+    if arguments.list {
+        for bench in &selected {
+            writeln!(out, "{}: benchmark", bench.name())?;
+        }
+
+        return Ok(true);
+    }
+
+    let noun = if selected.len() == 1 {
+        "benchmark"
+    } else {
+        "benchmarks"
+    };
+    writeln!(out, "\nrunning {} {}", selected.len(), noun)?;
+
+    let mut failed = Vec::new();
+
+    for bench in &selected {
+        let mut bencher = Bencher {
+            measure: arguments.bench,
+            stats: None,
+        };
+
+        let outcome = catch_panic(|| (bench.run)(&mut bencher));
+        let parameters = bench
+            .parameters
+            .iter()
+            .map(|(parameter, argument)| format!("{} = {}", parameter, argument))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match (outcome, bencher.stats) {
+            (Err(message), _) => {
+                writeln!(out, "bench {} ({}) ... FAILED", bench.name(), parameters)?;
+                failed.push((bench.name(), message));
+            }
+            (Ok(()), Some(stats)) => {
+                writeln!(out, "bench {} ({}) ... {}", bench.name(), parameters, stats)?
+            }
+            (Ok(()), None) => writeln!(out, "bench {} ({}) ... ok", bench.name(), parameters)?,
+        }
+    }
+
+    for (name, message) in &failed {
+        writeln!(out, "\n---- {} ----\n{}", name, message)?;
+    }
+
+    let result = if failed.is_empty() { "ok" } else { "FAILED" };
+    writeln!(
+        out,
+        "\nbench result: {}. {} passed; {} failed\n",
+        result,
+        selected.len() - failed.len(),
+        failed.len()
+    )?;
+
+    Ok(failed.is_empty())
+}
@@ -0,0 +1,242 @@
+//! The parameterized test case which is running on the current thread.
+//!
+//! Each generated test case enters its case before it calls the test function, which may then
+//! inspect the case by calling [`current_case`](crate::current_case).
+//!
+//! When the test function panics, or returns an `Err`, the index and arguments of the case are
+//! printed alongside the failure, for example:
+//!
+//! ```text
+//! parameterized case #7: input = u16::MAX (65535), expected = 4
+//! ```
+//!
+//! The `Debug` representation of an argument is only shown when its type implements `Debug`, and
+//! it differs from the expression given in the attribute. It's captured before the test function
+//! is called, and cut off after 256 bytes.
+
+use std::cell::Cell;
+use std::fmt;
+
+thread_local! {
+    static CURRENT_CASE: Cell<Option<CaseContext>> = const { Cell::new(None) };
+}
+
+/// Returns the parameterized test case which is running on the current thread, or `None` if
+/// this function isn't called from a test case generated by `#[parameterized]`.
+///
+/// The test case is tracked per thread: it's not available on threads spawned by the test
+/// function, nor within an `async` test function which is moved to another thread while it runs.
+pub fn current_case() -> Option<CaseContext> {
+    CURRENT_CASE.with(Cell::get)
+}
+
+/// A parameterized test case, as returned by [`current_case`](crate::current_case).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CaseContext {
+    index: usize,
+    label: &'static str,
+    total: usize,
+    arguments: &'static [(&'static str, &'static str)],
+}
+
+impl CaseContext {
+    #[doc(hidden)]
+    pub const fn new(
+        index: usize,
+        label: &'static str,
+        total: usize,
+        arguments: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        Self {
+            index,
+            label,
+            total,
+            arguments,
+        }
+    }
+
+    /// The index of the test case, in the argument lists of the attribute.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The name of the generated test case, relative to the module of the test function, e.g.
+    /// `add5::case_7`.
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    /// The amount of test cases given to the attribute, including those which are filtered out by
+    /// a constraint, or not selected by `sample`.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// The parameters of the test case, paired with the source text of their arguments, e.g.
+    /// `("input", "u16::MAX")`.
+    pub fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        self.arguments
+    }
+}
+
+/// Sets the current test case while it's alive, and reports the test case when it's dropped
+/// while the thread is panicking.
+#[doc(hidden)]
+pub struct Guard {
+    context: CaseContext,
+    values: Vec<Option<String>>,
+    report: bool,
+    previous: Option<CaseContext>,
+}
+
+impl Guard {
+    /// Enter a test case, with the `Debug` representation of each argument, if any.
+    pub fn enter(context: CaseContext, values: Vec<Option<String>>) -> Self {
+        Self::new(context, values, true)
+    }
+
+    /// Enter a test case, which is reported by the built-in test runner instead.
+    pub fn enter_silently(context: CaseContext) -> Self {
+        Self::new(context, Vec::new(), false)
+    }
+
+    fn new(context: CaseContext, values: Vec<Option<String>>, report: bool) -> Self {
+        let previous = CURRENT_CASE.with(|current| current.replace(Some(context)));
+
+        Self {
+            context,
+            values,
+            report,
+            previous,
+        }
+    }
+
+    /// Report the test case, if `failed` is set, since the test function returned an error.
+    pub fn exit(&self, failed: bool) {
+        if failed && self.report {
+            eprintln!("{}", self);
+        }
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        CURRENT_CASE.with(|current| current.set(self.previous));
+
+        if self.report && std::thread::panicking() {
+            eprintln!("{}", self);
+        }
+    }
+}
+
+impl fmt::Display for Guard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parameterized case #{}:", self.context.index)?;
+
+        for (i, (parameter, expression)) in self.context.arguments.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}{} = {}", separator, parameter, expression)?;
+
+            match self.values.get(i) {
+                Some(Some(value)) if value != expression => write!(f, " ({})", value)?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A value of a test case, which is inspected by the [`Inspect`] and [`InspectFallback`] traits.
+///
+/// Called as `(&Value(&value)).method()`, the method of [`Inspect`] is preferred when its bounds
+/// are met, and the method of [`InspectFallback`] is used otherwise.
+#[doc(hidden)]
+pub struct Value<'v, T>(pub &'v T);
+
+#[doc(hidden)]
+pub trait Inspect {
+    fn debug(&self) -> Option<String>;
+}
+
+impl<T: fmt::Debug> Inspect for Value<'_, T> {
+    fn debug(&self) -> Option<String> {
+        let mut representation = Bounded {
+            buffer: String::new(),
+            truncated: false,
+        };
+
+        // formatting is cut short by the error of a full buffer
+        let _ = fmt::write(&mut representation, format_args!("{:?}", self.0));
+
+        if representation.truncated {
+            representation.buffer.push_str("...");
+        }
+
+        Some(representation.buffer)
+    }
+}
+
+/// The length at which the `Debug` representation of an argument is cut off. The arguments are
+/// captured before the test function is called, by every test case, so a large argument isn't
+/// formatted as a whole.
+const MAX_DEBUG_LENGTH: usize = 256;
+
+/// A buffer which holds at most [`MAX_DEBUG_LENGTH`] bytes, and fails any write beyond it.
+struct Bounded {
+    buffer: String,
+    truncated: bool,
+}
+
+impl fmt::Write for Bounded {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let remaining = MAX_DEBUG_LENGTH - self.buffer.len();
+
+        if s.len() <= remaining {
+            self.buffer.push_str(s);
+            return Ok(());
+        }
+
+        let mut end = remaining;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        self.buffer.push_str(&s[..end]);
+        self.truncated = true;
+        Err(fmt::Error)
+    }
+}
+
+#[doc(hidden)]
+pub trait InspectFallback {
+    fn debug(&self) -> Option<String>;
+}
+
+impl<T> InspectFallback for &Value<'_, T> {
+    fn debug(&self) -> Option<String> {
+        None
+    }
+}
+
+#[doc(hidden)]
+pub trait Failure {
+    fn is_failure(&self) -> bool;
+}
+
+impl<T, E> Failure for Value<'_, Result<T, E>> {
+    fn is_failure(&self) -> bool {
+        self.0.is_err()
+    }
+}
+
+#[doc(hidden)]
+pub trait FailureFallback {
+    fn is_failure(&self) -> bool;
+}
+
+impl<T> FailureFallback for &Value<'_, T> {
+    fn is_failure(&self) -> bool {
+        false
+    }
+}
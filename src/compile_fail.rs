@@ -0,0 +1,222 @@
+//! Compile-fail test cases, as used by the `compile_fail` option of the `#[parameterized]`
+//! attribute.
+//!
+//! The test function returns the source of its test case: either a code snippet, or the path of
+//! a source file, relative to the root of the crate (a single line ending in `.rs`). The source is
+//! checked by `cargo check`, as the library of a small package in the 2021 edition, which depends
+//! on the crate under test, and which should fail to compile. Its errors are then compared with
+//! the expected stderr of the test case:
+//!
+//! * for a source file, the file next to it with the `.stderr` extension, e.g.
+//!   `tests/fail/missing_field.stderr`;
+//! * for a code snippet, `tests/snapshots/<module path>/<case>.stderr`, like the snapshots of the
+//!   [`snapshot`](crate::snapshot) option, e.g. `tests/snapshots/my_crate/tests/rejected/case_0.stderr`.
+//!
+//! The stderr files are created or rewritten when the `PARAMETERIZED_UPDATE` environment variable
+//! is set to `1`. Unlike a snapshot, a missing stderr file isn't created otherwise, since the test
+//! case would pass without checking the errors at all, so the test case fails instead.
+//!
+//! The packages of the test cases are generated within the target directory of the test, where
+//! they share a target directory of their own, so the crate under test is only built once. The
+//! source may use the crate under test, built with its default features, and the versions of its
+//! dependencies are taken from the `Cargo.lock` of its workspace. Warnings are allowed, unless the
+//! source denies them itself.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::snapshot::{assert_matches_file, module_directory, snapshot_path, Update};
+
+/// Check that `source`, the code snippet or source file of a test case, fails to compile with
+/// the expected errors. The source may use the crate under test, the `package` at
+/// `manifest_dir`.
+///
+/// # Panics
+///
+/// Panics if the source compiles, if its errors differ from the expected stderr, if the expected
+/// stderr doesn't exist, or if `cargo` can't be run.
+pub fn assert_compile_fail(
+    manifest_dir: &str,
+    package: &str,
+    module_path: &str,
+    case: &str,
+    source: &str,
+) {
+    let manifest_dir = Path::new(manifest_dir);
+
+    // each test case has its own package, since the cases may run in parallel
+    let project = work_directory()
+        .join(package)
+        .join(module_directory(module_path))
+        .join(case);
+
+    let (file, expected) = if is_path(source) {
+        let path = Path::new(source.trim());
+        if !manifest_dir.join(path).is_file() {
+            panic!("unable to find the source file {}", path.display());
+        }
+
+        (
+            manifest_dir.join(path),
+            manifest_dir.join(path.with_extension("stderr")),
+        )
+    } else {
+        // the snippet is shown by the compiler by its path relative to the package
+        let file = module_directory(module_path).join(format!("{}.rs", case));
+        write_file(&project.join(&file), source);
+
+        (
+            file,
+            snapshot_path(manifest_dir, module_path, &format!("{}.stderr", case)),
+        )
+    };
+
+    write_file(
+        &project.join("Cargo.toml"),
+        &manifest(manifest_dir, package, &file),
+    );
+    if let Some(lockfile) = lockfile(manifest_dir) {
+        if let Err(err) = fs::copy(&lockfile, project.join("Cargo.lock")) {
+            panic!("unable to copy {}: {}", lockfile.display(), err);
+        }
+    }
+
+    let stderr = check(&project).map(|stderr| {
+        // a source file outside of the package is shown by its absolute path
+        let root = format!("{}{}", manifest_dir.display(), std::path::MAIN_SEPARATOR);
+        stderr.replace(&root, "")
+    });
+
+    // a missing stderr file is only written when it's requested
+    let update = match Update::requested() {
+        Update::All => Update::All,
+        Update::Missing | Update::None => Update::None,
+    };
+
+    match stderr {
+        Some(stderr) => {
+            assert_matches_file(&expected, &stderr, "the output of the compiler", update)
+        }
+        None => panic!(
+            "the source of the test case compiled successfully, but it was expected to fail:\n{}",
+            file.display()
+        ),
+    }
+}
+
+/// A source which is a single line ending in `.rs` is the path of a source file.
+fn is_path(source: &str) -> bool {
+    let source = source.trim();
+    !source.contains('\n') && source.ends_with(".rs")
+}
+
+/// The manifest of the package of a test case, whose library is the source `file`, and which
+/// depends on the crate under test.
+fn manifest(manifest_dir: &Path, package: &str, file: &Path) -> String {
+    format!(
+        r#"[package]
+name = "parameterized-compile-fail"
+version = "0.0.0"
+edition = "2021"
+publish = false
+
+[lib]
+path = {}
+
+[dependencies]
+{} = {{ path = {} }}
+
+# the package isn't a member of the workspace which contains the target directory
+[workspace]
+"#,
+        toml_string(file),
+        package,
+        toml_string(manifest_dir),
+    )
+}
+
+/// A path as a TOML string.
+fn toml_string(path: &Path) -> String {
+    format!(
+        "\"{}\"",
+        path.display()
+            .to_string()
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+    )
+}
+
+/// The `Cargo.lock` of the workspace of the crate under test, if any, which pins the versions of
+/// its dependencies.
+fn lockfile(manifest_dir: &Path) -> Option<PathBuf> {
+    manifest_dir
+        .ancestors()
+        .map(|directory| directory.join("Cargo.lock"))
+        .find(|lockfile| lockfile.is_file())
+}
+
+/// Check the package at `project`, and return the normalized stderr of the compiler, or `None`
+/// if the package compiled.
+fn check(project: &Path) -> Option<String> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+
+    // the flags don't change between the test cases, so the crate under test isn't rebuilt
+    let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+    rustflags.push_str(" -A warnings");
+
+    let output = Command::new(cargo)
+        .current_dir(project)
+        .args(["check", "--lib", "--quiet", "--color", "never"])
+        .arg("--target-dir")
+        .arg(work_directory().join("target"))
+        .env("RUSTFLAGS", rustflags)
+        .output()
+        .unwrap_or_else(|err| panic!("unable to run cargo: {}", err));
+
+    if output.status.success() {
+        None
+    } else {
+        Some(normalize(&String::from_utf8_lossy(&output.stderr)))
+    }
+}
+
+/// The directory in which the packages of the test cases are generated and built: within the
+/// target directory of the test, so the crate under test is only built once, rather than by
+/// every run of the tests.
+fn work_directory() -> PathBuf {
+    // the test executable is found at `target/<profile>/deps/<test>`
+    std::env::current_exe()
+        .ok()
+        .and_then(|test| Some(test.parent()?.parent()?.join("parameterized-compile-fail")))
+        .unwrap_or_else(|| std::env::temp_dir().join("parameterized-compile-fail"))
+}
+
+fn write_file(path: &Path, contents: &str) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(path, contents));
+
+    if let Err(err) = result {
+        panic!("unable to write {}: {}", path.display(), err);
+    }
+}
+
+/// The summaries of `cargo` and the compiler at the end of the output depend on their versions,
+/// and are left out.
+fn normalize(stderr: &str) -> String {
+    stderr
+        .lines()
+        .filter(|line| {
+            !line.starts_with("error: aborting due to")
+                && !line.starts_with("error: could not compile")
+                && !line.starts_with("warning: build failed")
+                && !line.starts_with("For more information about")
+                && !line.starts_with("Some errors have detailed explanations")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
@@ -0,0 +1,42 @@
+//! A minimal executor, which runs the test cases of an `async` test function that isn't given a
+//! runtime or a test macro.
+//!
+//! It polls a single future on the current thread, and parks the thread while the future is
+//! pending, until it's woken. There's no reactor, so futures which rely on a runtime for I/O or
+//! timers (e.g. `tokio::time::sleep`) never complete; pure futures, and futures which are woken
+//! by other threads, such as channels, do.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// Wakes the thread which is blocked on a future.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run `future` to completion on the current thread, and return its output.
+///
+/// The thread is parked while the future is pending. A wake-up may be spurious, in which case
+/// the future is simply polled again.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -0,0 +1,819 @@
+//! A test runner for test targets which don't use the default test harness (`harness = false`).
+//!
+//! Such targets are not compiled with `cfg(test)`, so the test cases of `#[parameterized]`
+//! would be left out. With the `harness` option, the test cases are generated regardless, and
+//! registered in a `CASES` constant in the generated module, which can be given to [`main`]:
+//!
+//! ```rust,ignore
+//! use parameterized::parameterized;
+//!
+//! #[parameterized(input = { 0, 1 }, expected = { 5, 6 }, harness)]
+//! fn add5(input: u32, expected: u32) {
+//!     assert_eq!(input + 5, expected);
+//! }
+//!
+//! fn main() {
+//!     parameterized::harness::main(&[add5::CASES]);
+//! }
+//! ```
+//!
+//! Test cases which can only be computed at runtime can be added with an [`ArgumentsProvider`],
+//! using a [`Runner`].
+//!
+//! The runner accepts a subset of the arguments of the default test harness: test name filters,
+//! `--exact`, `--skip <filter>`, `--list`, `--ignored`, `--include-ignored`, `--quiet` and
+//! `--format <pretty|terse|json|junit>`.
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt::Debug;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+/// A single test case, as registered by the code generated for `#[parameterized(.., harness)]`.
+pub struct Case {
+    /// The index of the test case, i.e. its row in the parameter lists
+    pub index: usize,
+    /// The module path of the test case, including the crate name, e.g. `tests::add5::case_0`
+    pub path: &'static str,
+    /// The parameters of the test case, with their arguments as written
+    pub parameters: &'static [(&'static str, &'static str)],
+    /// Whether the test case was marked with `#[ignore]`
+    pub ignore: bool,
+    pub should_panic: ShouldPanic,
+    pub run: fn() -> Result<(), String>,
+}
+
+impl Case {
+    /// The name of the test case, without the crate name, like the default test harness.
+    pub fn name(&self) -> &'static str {
+        without_crate_name(self.path)
+    }
+}
+
+/// The path of a registered test case or benchmark, without its leading crate name.
+pub(crate) fn without_crate_name(path: &'static str) -> &'static str {
+    match path.find("::") {
+        Some(position) => &path[position + 2..],
+        None => path,
+    }
+}
+
+/// Whether a test case was marked with `#[should_panic]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShouldPanic {
+    No,
+    Yes,
+    /// `#[should_panic(expected = "..")]`, where the panic message must contain the given string
+    YesWithMessage(&'static str),
+}
+
+/// The return types of test cases, which can be converted to the outcome of a test case.
+pub trait Outcome {
+    fn into_result(self) -> Result<(), String>;
+}
+
+impl Outcome for () {
+    fn into_result(self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl<T, E: Debug> Outcome for Result<T, E> {
+    fn into_result(self) -> Result<(), String> {
+        self.map(|_| ()).map_err(|err| format!("Error: {:?}", err))
+    }
+}
+
+/// The report format of the runner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Pretty,
+    Terse,
+    Json,
+    Junit,
+}
+
+/// The command line arguments of the runner.
+#[derive(Clone, Debug)]
+pub struct Arguments {
+    pub filters: Vec<String>,
+    pub skip: Vec<String>,
+    pub exact: bool,
+    pub list: bool,
+    pub ignored: bool,
+    pub include_ignored: bool,
+    pub format: Format,
+    /// Whether the binary was run by `cargo bench`, rather than `cargo test`
+    pub bench: bool,
+}
+
+impl Default for Arguments {
+    fn default() -> Self {
+        Self {
+            filters: Vec::new(),
+            skip: Vec::new(),
+            exact: false,
+            list: false,
+            ignored: false,
+            include_ignored: false,
+            format: Format::Pretty,
+            bench: false,
+        }
+    }
+}
+
+impl Arguments {
+    /// Parse the arguments the test binary was invoked with.
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Parse the given arguments, excluding the name of the binary.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut arguments = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if arg.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+
+            let mut value = |name: &str| {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("argument for option '{}' missing", name))
+            };
+
+            match flag.as_str() {
+                "--list" => arguments.list = true,
+                "--ignored" => arguments.ignored = true,
+                "--include-ignored" => arguments.include_ignored = true,
+                "--exact" => arguments.exact = true,
+                "-q" | "--quiet" => arguments.format = Format::Terse,
+                "--skip" => arguments.skip.push(value("skip")?),
+                "--format" => {
+                    arguments.format = match value("format")?.as_str() {
+                        "pretty" => Format::Pretty,
+                        "terse" => Format::Terse,
+                        "json" => Format::Json,
+                        "junit" => Format::Junit,
+                        other => {
+                            return Err(format!(
+                            "argument for --format must be pretty, terse, json or junit (was {})",
+                            other
+                        ))
+                        }
+                    }
+                }
+                "--bench" => arguments.bench = true,
+                // accepted for compatibility with the default test harness, but without effect
+                "--nocapture" | "--show-output" | "--test" => {}
+                "--test-threads" | "--color" | "-Z" => {
+                    value(&flag)?;
+                }
+                _ if flag.starts_with('-') => {
+                    return Err(format!("Unrecognized option: '{}'", flag))
+                }
+                _ => arguments.filters.push(arg),
+            }
+        }
+
+        Ok(arguments)
+    }
+
+    pub(crate) fn is_selected(&self, name: &str) -> bool {
+        let matches = |filter: &String| {
+            if self.exact {
+                name == filter
+            } else {
+                name.contains(filter.as_str())
+            }
+        };
+
+        (self.filters.is_empty() || self.filters.iter().any(matches))
+            && !self.skip.iter().any(matches)
+    }
+}
+
+/// Run the given test cases with the arguments of the test binary, and exit the process with
+/// the same exit code the default test harness would use.
+pub fn main(cases: &[&'static [Case]]) -> ! {
+    cases
+        .iter()
+        .fold(Runner::new(), |runner, cases| runner.cases(cases))
+        .main()
+}
+
+/// Run the given test cases, and write the report to `out`. Returns whether all test cases
+/// which were run passed.
+pub fn run(
+    arguments: &Arguments,
+    cases: &[&'static [Case]],
+    out: &mut dyn Write,
+) -> io::Result<bool> {
+    cases
+        .iter()
+        .fold(Runner::new(), |runner, cases| runner.cases(cases))
+        .run(arguments, out)
+}
+
+/// Run each of the given test cases, and panic once with a summary of the failing cases, if any.
+///
+/// This is used by the single test generated by `#[parameterized(.., aggregate)]`, which runs
+/// all test cases of a test function. Ignored test cases are run as well, since the aggregated
+/// test itself is ignored instead. The summary is reported at the location of the caller.
+#[track_caller]
+pub fn aggregate(cases: &[Case]) {
+    let failures = cases
+        .iter()
+        .filter_map(|case| match execute(Test::from(case), false).status {
+            Status::Failed(message) => Some((case, message)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if failures.is_empty() {
+        return;
+    }
+
+    let mut summary = format!(
+        "{} of {} parameterized cases failed:\n",
+        failures.len(),
+        cases.len()
+    );
+
+    for (case, message) in failures {
+        let parameters = case
+            .parameters
+            .iter()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        summary.push_str(&format!(
+            "\ncase #{} ({}): {}\n",
+            case.index,
+            case.name(),
+            parameters
+        ));
+
+        for line in message.lines() {
+            summary.push_str(&format!("    {}\n", line));
+        }
+    }
+
+    panic!("{}", summary);
+}
+
+/// Provides named test cases at runtime, e.g. by walking a directory, for case sets which can't
+/// be written down in a `#[parameterized(..)]` attribute.
+///
+/// A provider is given to a [`Runner`] together with a test function, and each of the provided
+/// cases is run and reported as an individual test.
+pub trait ArgumentsProvider {
+    /// The arguments of a single test case, which are passed to the test function
+    type Arguments;
+    /// The test cases, each named and with their arguments
+    type Cases: IntoIterator<Item = (String, Self::Arguments)>;
+
+    fn provide(&self) -> Self::Cases;
+}
+
+/// Collects the test cases to run, both those registered by `#[parameterized(.., harness)]`, and
+/// those provided at runtime by an [`ArgumentsProvider`].
+///
+/// ```rust,ignore
+/// fn main() {
+///     parameterized::harness::Runner::new()
+///         .cases(add5::CASES)
+///         .factory("fixtures", FixtureFiles, check_fixture)
+///         .main();
+/// }
+/// ```
+#[derive(Default)]
+pub struct Runner {
+    sources: Vec<Box<dyn FnOnce() -> Vec<Test>>>,
+}
+
+impl Runner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the test cases registered in the `CASES` constant of a generated module.
+    pub fn cases(mut self, cases: &'static [Case]) -> Self {
+        self.sources
+            .push(Box::new(move || cases.iter().map(Test::from).collect()));
+        self
+    }
+
+    /// Add the test cases provided by `provider`, which are named `<name>::<case name>`, and
+    /// run by calling `test` with their arguments.
+    ///
+    /// The cases are provided when the runner starts. If the provider panics, this is reported
+    /// as a failure of a test named `name`.
+    pub fn factory<P, F, O>(mut self, name: &str, provider: P, test: F) -> Self
+    where
+        P: ArgumentsProvider + 'static,
+        P::Arguments: Debug + 'static,
+        F: Fn(P::Arguments) -> O + 'static,
+        O: Outcome,
+    {
+        let name = name.to_string();
+        let test = Rc::new(test);
+
+        self.sources.push(Box::new(move || {
+            let cases = match catch_panic(|| provider.provide().into_iter().collect::<Vec<_>>()) {
+                Ok(cases) => cases,
+                Err(message) => {
+                    return vec![Test {
+                        name,
+                        parameters: Vec::new(),
+                        ignore: false,
+                        should_panic: ShouldPanic::No,
+                        run: Box::new(move || {
+                            Err(format!("unable to provide the test cases: {}", message))
+                        }),
+                    }]
+                }
+            };
+
+            cases
+                .into_iter()
+                .map(|(case, arguments)| {
+                    let test = Rc::clone(&test);
+
+                    Test {
+                        name: format!("{}::{}", name, case),
+                        parameters: vec![("arguments".to_string(), format!("{:?}", arguments))],
+                        ignore: false,
+                        should_panic: ShouldPanic::No,
+                        run: Box::new(move || test(arguments).into_result()),
+                    }
+                })
+                .collect()
+        }));
+
+        self
+    }
+
+    /// Run the test cases with the arguments of the test binary, and exit the process with the
+    /// same exit code the default test harness would use.
+    pub fn main(self) -> ! {
+        let arguments = match Arguments::from_env() {
+            Ok(arguments) => arguments,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                std::process::exit(101);
+            }
+        };
+
+        match self.run(&arguments, &mut io::stdout()) {
+            Ok(true) => std::process::exit(0),
+            Ok(false) => std::process::exit(101),
+            Err(err) => {
+                eprintln!("error: unable to write the test report: {}", err);
+                std::process::exit(101);
+            }
+        }
+    }
+
+    /// Run the test cases, and write the report to `out`. Returns whether all test cases which
+    /// were run passed.
+    pub fn run(self, arguments: &Arguments, out: &mut dyn Write) -> io::Result<bool> {
+        let all = self
+            .sources
+            .into_iter()
+            .flat_map(|source| source())
+            .collect::<Vec<_>>();
+        let total = all.len();
+
+        let selected = all
+            .into_iter()
+            .filter(|test| arguments.is_selected(&test.name))
+            .filter(|test| !arguments.ignored || test.ignore)
+            .collect::<Vec<_>>();
+
+        let filtered_out = total - selected.len();
+
+        if arguments.list {
+            return list(arguments, &selected, out).map(|_| true);
+        }
+
+        let started = Instant::now();
+        let mut reports = Vec::with_capacity(selected.len());
+
+        match arguments.format {
+            Format::Pretty | Format::Terse => {
+                let noun = if selected.len() == 1 { "test" } else { "tests" };
+                writeln!(out, "\nrunning {} {}", selected.len(), noun)?
+            }
+            Format::Json => writeln!(
+                out,
+                r#"{{ "type": "suite", "event": "started", "test_count": {} }}"#,
+                selected.len()
+            )?,
+            Format::Junit => {}
+        }
+
+        for test in selected {
+            if arguments.format == Format::Json {
+                writeln!(
+                    out,
+                    r#"{{ "type": "test", "event": "started", "name": "{}" }}"#,
+                    escape_json(&test.name)
+                )?;
+            }
+
+            let skip = test.ignore && !arguments.ignored && !arguments.include_ignored;
+            let report = execute(test, skip);
+
+            write_result(arguments.format, &report, out)?;
+            reports.push(report);
+        }
+
+        let elapsed = started.elapsed();
+        let summary = Summary::new(&reports, filtered_out);
+
+        match arguments.format {
+            Format::Pretty | Format::Terse => write_summary(&reports, &summary, elapsed, out)?,
+            Format::Json => writeln!(
+                out,
+                r#"{{ "type": "suite", "event": "{}", "passed": {}, "failed": {}, "ignored": {}, "measured": 0, "filtered_out": {}, "exec_time": {} }}"#,
+                if summary.failed == 0 { "ok" } else { "failed" },
+                summary.passed,
+                summary.failed,
+                summary.ignored,
+                summary.filtered_out,
+                elapsed.as_secs_f64(),
+            )?,
+            Format::Junit => write_junit(&reports, &summary, elapsed, out)?,
+        }
+
+        Ok(summary.failed == 0)
+    }
+}
+
+/// A test which is run by the [`Runner`]; either a registered [`Case`], or a case provided at
+/// runtime.
+struct Test {
+    name: String,
+    parameters: Vec<(String, String)>,
+    ignore: bool,
+    should_panic: ShouldPanic,
+    run: Box<dyn FnOnce() -> Result<(), String>>,
+}
+
+impl From<&Case> for Test {
+    fn from(case: &Case) -> Self {
+        Test {
+            name: case.name().to_string(),
+            parameters: case
+                .parameters
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            ignore: case.ignore,
+            should_panic: case.should_panic,
+            run: Box::new(case.run),
+        }
+    }
+}
+
+enum Status {
+    Passed,
+    Failed(String),
+    Ignored,
+}
+
+struct Report {
+    name: String,
+    parameters: Vec<(String, String)>,
+    status: Status,
+    duration: Duration,
+}
+
+fn list(arguments: &Arguments, tests: &[Test], out: &mut dyn Write) -> io::Result<()> {
+    for test in tests {
+        writeln!(out, "{}: test", test.name)?;
+    }
+
+    if arguments.format == Format::Pretty {
+        let noun = if tests.len() == 1 { "test" } else { "tests" };
+        writeln!(out, "\n{} {}, 0 benchmarks", tests.len(), noun)?;
+    }
+
+    Ok(())
+}
+
+thread_local! {
+    /// Whether a panic on this thread is caught by `catch_panic`
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+fn execute(test: Test, skip: bool) -> Report {
+    let Test {
+        name,
+        parameters,
+        should_panic,
+        run,
+        ..
+    } = test;
+
+    let started = Instant::now();
+    let status = if skip {
+        Status::Ignored
+    } else {
+        match (catch_panic(run), should_panic) {
+            (Ok(Ok(())), ShouldPanic::No) => Status::Passed,
+            (Ok(Err(err)), _) => Status::Failed(err),
+            (Ok(Ok(())), _) => Status::Failed("test did not panic as expected".to_string()),
+            (Err(message), ShouldPanic::No) => Status::Failed(message),
+            (Err(_), ShouldPanic::Yes) => Status::Passed,
+            (Err(message), ShouldPanic::YesWithMessage(expected)) => {
+                if message.contains(expected) {
+                    Status::Passed
+                } else {
+                    Status::Failed(format!(
+                        "panic did not contain expected string\n      panic message: `{:?}`,\n expected substring: `{:?}`",
+                        message, expected
+                    ))
+                }
+            }
+        }
+    };
+
+    Report {
+        name,
+        parameters,
+        status,
+        duration: started.elapsed(),
+    }
+}
+
+/// Call `f`, and catch a panic, which is returned as its message, prefixed by its location.
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    // the default panic hook would print the panic message, while it's part of the report
+    // instead; the hook is installed once, since tests on other threads may panic meanwhile
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CATCHING.with(Cell::get) {
+                let location = info.location().map(|location| location.to_string());
+                PANIC_LOCATION.with(|cell| *cell.borrow_mut() = location);
+            } else {
+                previous(info);
+            }
+        }));
+    });
+
+    let catching = CATCHING.with(|cell| cell.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|cell| cell.set(catching));
+
+    result.map_err(|payload| {
+        let location = PANIC_LOCATION.with(|cell| cell.borrow_mut().take());
+        let message = panic_message(payload.as_ref());
+
+        match location {
+            Some(location) => format!("panicked at {}:\n{}", location, message),
+            None => message,
+        }
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+fn write_result(format: Format, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    let name = &report.name;
+
+    match (format, &report.status) {
+        (Format::Pretty, Status::Passed) => writeln!(out, "test {} ... ok", name),
+        (Format::Pretty, Status::Failed(_)) => writeln!(out, "test {} ... FAILED", name),
+        (Format::Pretty, Status::Ignored) => writeln!(out, "test {} ... ignored", name),
+        (Format::Terse, Status::Passed) => write!(out, "."),
+        (Format::Terse, Status::Failed(_)) => write!(out, "F"),
+        (Format::Terse, Status::Ignored) => write!(out, "i"),
+        (Format::Json, status) => {
+            let (event, stdout) = match status {
+                Status::Passed => ("ok", None),
+                Status::Failed(message) => ("failed", Some(message)),
+                Status::Ignored => ("ignored", None),
+            };
+
+            let parameters = report
+                .parameters
+                .iter()
+                .map(|(name, value)| {
+                    format!(r#""{}": "{}""#, escape_json(name), escape_json(value))
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let stdout = stdout
+                .map(|message| format!(r#", "stdout": "{}""#, escape_json(message)))
+                .unwrap_or_default();
+
+            writeln!(
+                out,
+                r#"{{ "type": "test", "name": "{}", "event": "{}", "parameters": {{ {} }}, "exec_time": {}{} }}"#,
+                escape_json(name),
+                event,
+                parameters,
+                report.duration.as_secs_f64(),
+                stdout,
+            )
+        }
+        (Format::Junit, _) => Ok(()),
+    }
+}
+
+struct Summary {
+    passed: usize,
+    failed: usize,
+    ignored: usize,
+    filtered_out: usize,
+}
+
+impl Summary {
+    fn new(reports: &[Report], filtered_out: usize) -> Self {
+        let count =
+            |f: fn(&Status) -> bool| reports.iter().filter(|report| f(&report.status)).count();
+
+        Self {
+            passed: count(|status| matches!(status, Status::Passed)),
+            failed: count(|status| matches!(status, Status::Failed(_))),
+            ignored: count(|status| matches!(status, Status::Ignored)),
+            filtered_out,
+        }
+    }
+}
+
+fn write_summary(
+    reports: &[Report],
+    summary: &Summary,
+    elapsed: Duration,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let failures = reports
+        .iter()
+        .filter_map(|report| match &report.status {
+            Status::Failed(message) => Some((report, message)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if !failures.is_empty() {
+        writeln!(out, "\nfailures:\n")?;
+
+        for (report, message) in &failures {
+            writeln!(out, "---- {} ----", report.name)?;
+            if !report.parameters.is_empty() {
+                writeln!(out, "parameters: {}", describe_parameters(report))?;
+            }
+            writeln!(out, "{}\n", message)?;
+        }
+
+        writeln!(out, "\nfailures:")?;
+        for (report, _) in &failures {
+            writeln!(out, "    {}", report.name)?;
+        }
+    }
+
+    writeln!(
+        out,
+        "\ntest result: {}. {} passed; {} failed; {} ignored; 0 measured; {} filtered out; finished in {:.2}s\n",
+        if summary.failed == 0 { "ok" } else { "FAILED" },
+        summary.passed,
+        summary.failed,
+        summary.ignored,
+        summary.filtered_out,
+        elapsed.as_secs_f64(),
+    )
+}
+
+fn describe_parameters(report: &Report) -> String {
+    report
+        .parameters
+        .iter()
+        .map(|(name, value)| format!("{} = {}", name, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn write_junit(
+    reports: &[Report],
+    summary: &Summary,
+    elapsed: Duration,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(out, "<testsuites>")?;
+    writeln!(
+        out,
+        r#"  <testsuite name="test" package="test" id="0" errors="0" failures="{}" tests="{}" skipped="{}" time="{:.3}">"#,
+        summary.failed,
+        reports.len(),
+        summary.ignored,
+        elapsed.as_secs_f64(),
+    )?;
+
+    for report in reports {
+        let name = report.name.as_str();
+        let (class_name, test_name) = match name.rfind("::") {
+            Some(position) => (&name[..position], &name[position + 2..]),
+            None => ("", name),
+        };
+
+        writeln!(
+            out,
+            r#"    <testcase classname="{}" name="{}" time="{:.3}">"#,
+            escape_xml(class_name),
+            escape_xml(test_name),
+            report.duration.as_secs_f64(),
+        )?;
+
+        if !report.parameters.is_empty() {
+            writeln!(out, "      <properties>")?;
+            for (parameter, value) in &report.parameters {
+                writeln!(
+                    out,
+                    r#"        <property name="{}" value="{}"/>"#,
+                    escape_xml(parameter),
+                    escape_xml(value)
+                )?;
+            }
+            writeln!(out, "      </properties>")?;
+        }
+
+        match &report.status {
+            Status::Passed => {}
+            Status::Failed(message) => writeln!(
+                out,
+                r#"      <failure type="assert" message="{}"/>"#,
+                escape_xml(message)
+            )?,
+            Status::Ignored => writeln!(out, "      <skipped/>")?,
+        }
+
+        writeln!(out, "    </testcase>")?;
+    }
+
+    writeln!(out, "  </testsuite>")?;
+    writeln!(out, "</testsuites>")
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
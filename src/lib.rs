@@ -1,6 +1,20 @@
 #![doc = include_str!("../README.md")]
 
-pub use parameterized_macro::parameterized;
+pub use parameterized_macro::{parameterized, parameterized_bench};
+
+// lets the code generated within this crate refer to `::parameterized`, like it would elsewhere
+#[cfg(test)]
+extern crate self as parameterized;
+
+pub mod bench;
+pub mod case;
+pub mod compile_fail;
+pub mod executor;
+pub mod harness;
+pub mod snapshot;
+
+pub use case::{current_case, CaseContext};
+pub use executor::block_on;
 
 /// Attribute macro's such as 'parameterized' do not enable the run tests intent for a module
 /// marked as cfg(test) (or a #[test] function for that matter) in Intellij.
@@ -23,7 +37,6 @@ macro_rules! ide {
 
 #[cfg(test)]
 mod tests {
-    use crate::ide;
     use crate::parameterized;
 
     fn add5<T: Into<u32>>(component: T) -> u32 {
@@ -58,6 +71,7 @@ mod tests {
         }
     }
 
+    #[allow(clippy::unnecessary_literal_unwrap)]
     mod transitive_attrs {
         use super::*;
 
@@ -70,6 +84,7 @@ mod tests {
         }
     }
 
+    #[allow(clippy::assertions_on_constants)]
     mod fn_signatures {
         use super::*;
 
@@ -87,11 +102,556 @@ mod tests {
         }
 
         #[parameterized(_input = { 0, 1, 2 })]
+        #[allow(clippy::unused_unit)]
         const fn return_type(_input: u8) -> () {
             assert!(true)
         }
     }
 
+    mod const_eval {
+        use super::*;
+
+        ide!();
+
+        const fn gcd(a: u32, b: u32) -> u32 {
+            if b == 0 {
+                a
+            } else {
+                gcd(b, a % b)
+            }
+        }
+
+        #[parameterized(a = { 12, 17, 0 }, b = { 18, 5, 7 }, expected = { 6, 1, 7 }, const_eval)]
+        const fn common_divisor(a: u32, b: u32, expected: u32) {
+            assert!(gcd(a, b) == expected);
+        }
+
+        #[parameterized(input = { [1, 2, 3], [0; 3] }, const_eval, flat)]
+        const fn sum(input: [u8; 3], #[case_index] index: usize) -> u8 {
+            let total = input[0] + input[1] + input[2];
+            assert!((total == 0) == (index == 1));
+            total
+        }
+
+        #[parameterized(value = { 1, 2 }, const_eval, module = generic_size)]
+        const fn size<T>(value: T) -> usize {
+            let size = std::mem::size_of::<T>();
+            std::mem::forget(value);
+            size
+        }
+
+        // without a module, the test function is defined next to the constants, and can still
+        // be called by regular code
+        #[test]
+        fn callable() {
+            assert_eq!(sum([1, 1, 1], 0), 3);
+        }
+    }
+
+    mod stacked_test_macros {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(input = { 1, 3 })]
+        #[parameterized_macro(tokio::test(flavor = "multi_thread"), should_panic(expected = "odd"))]
+        async fn listed(input: u8) {
+            tokio::task::yield_now().await;
+            assert!(input % 2 == 0, "odd input");
+        }
+
+        #[parameterized(input = { 1, 3 })]
+        #[parameterized_macro(tokio::test)]
+        #[parameterized_macro(should_panic(expected = "odd"))]
+        async fn stacked(input: u8) {
+            tokio::task::yield_now().await;
+            assert!(input % 2 == 0, "odd input");
+        }
+    }
+
+    mod test_macro_key {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(test_macro = tokio::test(flavor = "multi_thread"), input = { 1, 2 })]
+        async fn multi_thread(input: u8) {
+            let flavor = tokio::runtime::Handle::current().runtime_flavor();
+            assert_eq!(flavor, tokio::runtime::RuntimeFlavor::MultiThread);
+            assert!(input > 0);
+        }
+
+        #[parameterized(input = { 1, 3 }, test_macro = [tokio::test, should_panic(expected = "odd")])]
+        async fn listed(input: u8) {
+            assert!(input % 2 == 0, "odd input");
+        }
+
+        #[parameterized(input = { 1, 2 }, test_macro = test)]
+        fn plain(input: u8) {
+            assert!(input > 0);
+        }
+    }
+
+    mod runtime {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(input = { 1, 2 }, runtime = tokio)]
+        async fn current_thread(input: u8) {
+            let handle = tokio::spawn(async move { input * 2 });
+            assert_eq!(handle.await.unwrap(), input * 2);
+        }
+
+        #[parameterized(input = { 1, 2 }, runtime = tokio(flavor = "multi_thread", worker_threads = 2))]
+        async fn multi_thread(input: u8) {
+            let flavor = tokio::runtime::Handle::current().runtime_flavor();
+            assert_eq!(flavor, tokio::runtime::RuntimeFlavor::MultiThread);
+            assert!(input > 0);
+        }
+
+        #[parameterized(input = { "1", "2" }, runtime = smol, where = |input| input != "2")]
+        async fn blocking(input: &str) -> Result<(), std::num::ParseIntError> {
+            let parsed = smol::unblock({
+                let input = input.to_string();
+                move || input.parse::<u8>()
+            })
+            .await?;
+
+            assert_eq!(crate::current_case().unwrap().index(), 0);
+            assert_eq!(parsed, 1);
+            Ok(())
+        }
+    }
+
+    mod executor {
+        use super::*;
+        use crate::block_on;
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::sync::{Arc, Mutex};
+        use std::task::{Context, Poll};
+        use std::thread;
+        use std::time::Duration;
+
+        ide!();
+
+        async fn double(value: u32) -> u32 {
+            value * 2
+        }
+
+        #[parameterized(input = { 1, 2, 3 }, expected = { 2, 4, 6 })]
+        async fn without_runtime(input: u32, expected: u32) {
+            assert_eq!(double(input).await, expected);
+            assert_eq!(crate::current_case().unwrap().total(), 3);
+        }
+
+        #[parameterized(input = { "1", "2" })]
+        async fn returns_result(input: &str) -> Result<(), std::num::ParseIntError> {
+            let parsed = async { input.parse::<u8>() }.await?;
+            assert!(parsed > 0);
+            Ok(())
+        }
+
+        /// A future which is pending until another thread completes it, and wakes its waker.
+        #[derive(Default)]
+        struct Handoff {
+            value: Arc<Mutex<Option<u32>>>,
+            spawned: bool,
+        }
+
+        impl Future for Handoff {
+            type Output = u32;
+
+            fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+                if let Some(value) = *self.value.lock().unwrap() {
+                    return Poll::Ready(value);
+                }
+
+                if !self.spawned {
+                    self.spawned = true;
+                    let value = Arc::clone(&self.value);
+                    let waker = cx.waker().clone();
+
+                    thread::spawn(move || {
+                        thread::sleep(Duration::from_millis(10));
+                        *value.lock().unwrap() = Some(42);
+                        waker.wake();
+                    });
+                }
+
+                Poll::Pending
+            }
+        }
+
+        #[test]
+        fn woken_by_another_thread() {
+            assert_eq!(block_on(Handoff::default()), 42);
+        }
+    }
+
+    mod helper_function {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(n = { 0, 1, 5 }, expected = { 1, 1, 120 })]
+        fn recursive(n: u64, expected: u64) {
+            if n > 1 {
+                recursive(n - 1, expected / n);
+            }
+
+            assert_eq!((1..=n).product::<u64>(), expected);
+        }
+
+        #[parameterized(shadow = { 1, 2 })]
+        fn shadow(shadow: u8) {
+            assert!(shadow > 0);
+        }
+    }
+
+    mod case_report {
+        use super::*;
+        use crate::case::{CaseContext, Guard, Inspect as _, InspectFallback as _, Value};
+
+        ide!();
+
+        #[parameterized(input = { 1, u16::MAX }, expected = { 1, u16::MAX })]
+        #[should_panic]
+        fn reported(input: u16, expected: u16) {
+            assert_ne!(input, expected);
+        }
+
+        struct NoDebug;
+
+        // the borrow is required for the fallback, as in the generated code
+        #[test]
+        #[allow(clippy::needless_borrow)]
+        fn debug_values() {
+            assert_eq!((&Value(&u16::MAX)).debug(), Some("65535".to_string()));
+            assert_eq!((&Value(&NoDebug)).debug(), None);
+        }
+
+        #[test]
+        #[allow(clippy::needless_borrow)]
+        fn debug_values_cut_off() {
+            let debug = (&Value(&vec![0u8; 1000])).debug().unwrap();
+
+            assert_eq!(debug.len(), 256 + "...".len());
+            assert!(debug.starts_with("[0, 0, "));
+            assert!(debug.ends_with("..."));
+
+            let debug = (&Value(&"é".repeat(200))).debug().unwrap();
+            assert_eq!(debug.chars().filter(|c| *c == 'é').count(), 127);
+        }
+
+        #[test]
+        fn display() {
+            let guard = Guard::enter(
+                CaseContext::new(
+                    7,
+                    "add5::case_7",
+                    8,
+                    &[("input", "u16::MAX"), ("expected", "4"), ("v", "NoDebug")],
+                ),
+                vec![Some("65535".to_string()), Some("4".to_string()), None],
+            );
+
+            assert_eq!(
+                guard.to_string(),
+                "parameterized case #7: input = u16::MAX (65535), expected = 4, v = NoDebug"
+            );
+        }
+    }
+
+    mod case_context {
+        use super::*;
+        use crate::current_case;
+
+        ide!();
+
+        #[parameterized(input = { 1, 2 + 3, 4 }, exclude = { 4 })]
+        fn context(input: u8) {
+            let case = current_case().unwrap();
+
+            assert_eq!(case.arguments()[0].0, "input");
+            assert_eq!(case.total(), 3);
+
+            match case.index() {
+                0 => assert_eq!(case.arguments(), [("input", "1")]),
+                1 => assert_eq!(case.arguments(), [("input", "2 + 3")]),
+                _ => unreachable!(),
+            }
+
+            assert_eq!(case.label(), format!("context::case_{}", case.index()));
+            assert!(input > 0);
+        }
+
+        #[parameterized(input = { 'a', 'b', 'c' })]
+        fn index(input: char, #[case_index] i: usize) {
+            assert_eq!(input, ['a', 'b', 'c'][i]);
+            assert_eq!(current_case().map(|case| case.index()), Some(i));
+        }
+
+        #[parameterized(input = { 'a', 'b' }, flat)]
+        fn index_flat(#[case_index] i: u8, input: char) {
+            assert_eq!(input as u8 - b'a', i);
+        }
+
+        #[test]
+        fn outside_of_a_case() {
+            assert!(current_case().is_none());
+        }
+    }
+
+    mod returns {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(input = { 0, 1, u32::MAX - 5 }, returns = { 5, 6, u32::MAX })]
+        fn add5(input: u32) -> u32 {
+            input + 5
+        }
+
+        #[parameterized(input = { "a", "b" }, returns = { "A", "B" })]
+        fn uppercase(input: &str) -> String {
+            input.to_uppercase()
+        }
+
+        #[parameterized(input = { "1", "x" }, returns = { Ok(1), Err(()) })]
+        fn parse(input: &str) -> Result<u8, ()> {
+            input.parse().map_err(|_| ())
+        }
+
+        #[parameterized(input = { 1, 2 }, returns = { 3, 4 })]
+        #[should_panic(expected = "differs from the expected value")]
+        fn unexpected(input: u8) -> u8 {
+            input + 1
+        }
+
+        #[parameterized(input = { 1, 2 }, returns = { 2, 4 })]
+        #[parameterized_macro(tokio::test)]
+        async fn asynchronous(input: u8) -> u8 {
+            input * 2
+        }
+    }
+
+    mod expect {
+        use super::*;
+
+        ide!();
+
+        #[derive(Debug, PartialEq)]
+        enum ParseError {
+            Empty,
+            Overflow { value: String },
+        }
+
+        fn parse(input: &str) -> Result<u8, ParseError> {
+            match input {
+                "" => Err(ParseError::Empty),
+                _ => input.parse().map_err(|_| ParseError::Overflow {
+                    value: input.to_string(),
+                }),
+            }
+        }
+
+        #[parameterized(input = { "1", "", "256" }, expect = {
+            Ok(_),
+            Err(ParseError::Empty),
+            Err(ParseError::Overflow { .. }),
+        })]
+        fn parsed(input: &str) -> Result<u8, ParseError> {
+            parse(input)
+        }
+
+        #[parameterized(input = { 0, 1, 2 }, expect = { None, Some(1) | Some(2), Some(2..=3) })]
+        fn checked(input: u8) -> Option<u8> {
+            input
+                .checked_sub(1)
+                .map(|value| value + 1)
+                .filter(|value| *value > 0)
+        }
+
+        #[parameterized(input = { "" }, expect = { Ok(_) })]
+        #[should_panic(
+            expected = "doesn't match the expected pattern `Ok(_)`\n  returned: Err(Empty)"
+        )]
+        fn mismatch(input: &str) -> Result<u8, ParseError> {
+            parse(input)
+        }
+    }
+
+    mod oracle {
+        use super::*;
+
+        ide!();
+
+        mod reference {
+            pub fn count_ones(value: u32) -> u32 {
+                (0..32).filter(|bit| value & (1 << bit) != 0).count() as u32
+            }
+
+            pub fn reverse(values: Vec<u8>) -> Vec<u8> {
+                let mut reversed = Vec::new();
+                for value in values {
+                    reversed.insert(0, value);
+                }
+                reversed
+            }
+        }
+
+        #[parameterized(value = { 0, 1, 0b1011, u32::MAX }, oracle = reference::count_ones)]
+        fn count_ones(value: u32) -> u32 {
+            value.count_ones()
+        }
+
+        #[parameterized(values = { vec![], vec![1, 2, 3] }, oracle = reference::reverse)]
+        fn reverse(mut values: Vec<u8>) -> Vec<u8> {
+            values.reverse();
+            values
+        }
+
+        static EVALUATIONS: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+        fn evaluate(value: u32) -> u32 {
+            EVALUATIONS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            value
+        }
+
+        // the argument expression is evaluated once, and its clone is given to the oracle
+        #[parameterized(value = { evaluate(0b1011) }, oracle = reference::count_ones)]
+        fn evaluated_once(value: u32) -> u32 {
+            assert_eq!(EVALUATIONS.load(std::sync::atomic::Ordering::SeqCst), 1);
+            value.count_ones()
+        }
+
+        // the index of the test case isn't given to the oracle
+        #[parameterized(value = { 0b1, 0b11 }, oracle = reference::count_ones)]
+        fn with_case_index(value: u32, #[case_index] i: usize) -> u32 {
+            assert_eq!(value.count_ones() as usize, i + 1);
+            value.count_ones()
+        }
+
+        #[parameterized(value = { 2, 3 }, oracle = reference::count_ones)]
+        #[should_panic(
+            expected = "differs from the value returned by the oracle `reference::count_ones`"
+        )]
+        fn wrong(value: u32) -> u32 {
+            value
+        }
+    }
+
+    mod snapshot {
+        use super::*;
+        use crate::snapshot::{assert_matches_file, snapshot_path, Update};
+
+        ide!();
+
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        struct Split<'s> {
+            head: &'s str,
+            tail: Option<&'s str>,
+        }
+
+        #[parameterized(input = { "a", "a,b", "a,b,c" }, snapshot)]
+        fn split_once(input: &str) -> Split<'_> {
+            match input.split_once(',') {
+                Some((head, tail)) => Split {
+                    head,
+                    tail: Some(tail),
+                },
+                None => Split {
+                    head: input,
+                    tail: None,
+                },
+            }
+        }
+
+        #[parameterized(input = { 1, 2 }, snapshot = display, module = display_snapshots)]
+        fn table(input: u8) -> String {
+            (1..=3)
+                .map(|i| format!("{} x {} = {}\n", i, input, i * input))
+                .collect()
+        }
+
+        fn manifest_dir(name: &str) -> std::path::PathBuf {
+            let dir = std::env::temp_dir()
+                .join("parameterized-snapshots")
+                .join(format!("{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            dir
+        }
+
+        // test functions with the same name in different modules have their own snapshots
+        mod first {
+            use super::*;
+
+            #[parameterized(input = { "a,b" }, snapshot)]
+            fn parse(input: &str) -> Vec<&str> {
+                input.split(',').collect()
+            }
+        }
+
+        mod second {
+            use super::*;
+
+            #[parameterized(input = { "a,b" }, snapshot)]
+            fn parse(input: &str) -> usize {
+                input.len()
+            }
+        }
+
+        #[test]
+        fn created_then_compared() {
+            let dir = manifest_dir("created");
+            let path = snapshot_path(&dir, "my_crate::split", "case_0.snap");
+            assert!(path.ends_with("tests/snapshots/my_crate/split/case_0.snap"));
+
+            assert_matches_file(&path, "a\nb", "the value", Update::Missing);
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\nb\n");
+
+            assert_matches_file(&path, "a\r\nb\n", "the value", Update::Missing);
+            assert_matches_file(&path, "a\r\nb\n", "the value", Update::None);
+        }
+
+        #[test]
+        fn missing() {
+            let dir = manifest_dir("missing");
+            let path = snapshot_path(&dir, "my_crate::split", "case_0.snap");
+
+            let payload = std::panic::catch_unwind(|| {
+                assert_matches_file(&path, "a\nb", "the value", Update::None)
+            })
+            .unwrap_err();
+            let message = payload.downcast_ref::<String>().unwrap();
+
+            assert!(message.contains("doesn't exist"));
+            assert!(message.contains("PARAMETERIZED_UPDATE=1"));
+            assert!(message.ends_with("\n\na\nb"));
+            assert!(!path.exists());
+        }
+
+        #[test]
+        fn mismatch() {
+            let dir = manifest_dir("mismatch");
+            let path = snapshot_path(&dir, "my_crate::split", "case_0.snap");
+            assert_matches_file(&path, "a\nb\nc", "the value", Update::All);
+
+            let payload = std::panic::catch_unwind(|| {
+                assert_matches_file(&path, "a\nx\nc\nd", "the value", Update::Missing)
+            })
+            .unwrap_err();
+            let message = payload.downcast_ref::<String>().unwrap();
+
+            assert!(message.contains("differs from the snapshot"));
+            assert!(message.contains("PARAMETERIZED_UPDATE=1"));
+            assert!(message.ends_with(" a\n-b\n+x\n c\n+d\n"));
+        }
+    }
+
     mod custom_test_attribute {
         use super::*;
 
@@ -109,4 +669,648 @@ mod tests {
             assert!(input)
         }
     }
+
+    mod constraints {
+        use super::*;
+
+        ide!();
+
+        #[derive(Debug, PartialEq)]
+        enum Mode {
+            Fast,
+            Slow,
+        }
+
+        const NO_THREADS: usize = 0;
+        const LIMIT: u8 = 10;
+
+        #[parameterized(mode = {
+            Mode::Fast, Mode::Fast, Mode::Slow, Mode::Fast
+        }, threads = {
+            0, 4, 0, NO_THREADS
+        }, exclude = {
+            (Mode::Fast, 0)
+        })]
+        fn excluded(mode: Mode, threads: usize) {
+            assert!(!(mode == Mode::Fast && threads == 0))
+        }
+
+        #[parameterized(mode = { Mode::Fast, Mode::Slow }, threads = { 0, 0 }, exclude = { (_, 0) })]
+        fn excluded_with_wildcard(mode: Mode, threads: usize) {
+            unreachable!(
+                "{:?} with {} threads should have been excluded",
+                mode, threads
+            )
+        }
+
+        #[parameterized(input = { 1, 2, 3, LIMIT }, where = |input| input < 3)]
+        fn where_clause(input: u8) {
+            assert!(input < 3)
+        }
+
+        #[parameterized(input = { 1, LIMIT }, where = |input| input < 3)]
+        fn where_clause_with_return_type(input: u8) -> Result<(), String> {
+            if input < 3 {
+                Ok(())
+            } else {
+                Err(format!("{} should have been skipped", input))
+            }
+        }
+    }
+
+    mod sampling {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(input = {
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9
+        }, sample = 3, seed = 42)]
+        fn sampled(input: u8) {
+            assert!(input < 10)
+        }
+
+        #[parameterized(input = { 0, 1, 2 }, sample = 2, seed = env("PARAMETERIZED_UNSET_SEED"))]
+        fn sampled_with_seed_from_env(input: u8) {
+            assert!(input < 3)
+        }
+
+        #[test]
+        fn seed_is_recorded() {
+            assert_eq!(sampled::PARAMETERIZED_SEED, 42);
+        }
+    }
+
+    mod generics {
+        use super::*;
+
+        ide!();
+
+        trait Codec: Copy + Default + PartialEq + std::fmt::Debug {
+            fn encode(self) -> u64;
+            fn decode(encoded: u64) -> Self;
+        }
+
+        macro_rules! impl_codec {
+            ($($ty:ty),*) => {
+                $(impl Codec for $ty {
+                    fn encode(self) -> u64 {
+                        self as u64
+                    }
+
+                    fn decode(encoded: u64) -> Self {
+                        encoded as $ty
+                    }
+                })*
+            };
+        }
+
+        impl_codec!(u8, u16, u64);
+
+        #[parameterized(T = type { u8, u16, u64 }, N = const { 1, 4, 16 })]
+        fn roundtrip<T: Codec, const N: usize>() {
+            let values = [T::default(); N];
+
+            for value in values {
+                assert_eq!(T::decode(value.encode()), value);
+            }
+        }
+
+        #[parameterized(T = type { u8, u64 }, input = { 1, 2 }, N = const { 2, 3 }, bytes = { [1, 1], [2, 2, 2] })]
+        fn in_parameter_types<T, const N: usize>(input: T, bytes: [T; N])
+        where
+            T: Codec + From<u8>,
+        {
+            assert!(bytes.iter().all(|byte| *byte == input));
+            assert_eq!(T::from(bytes.len() as u8 - 1), input);
+        }
+
+        #[parameterized(input = { "a", "bc" }, expected = { 1, 2 })]
+        fn lifetimes<'a>(input: &'a str, expected: usize) {
+            let borrowed: &'a str = input;
+            assert_eq!(borrowed.len(), expected);
+        }
+
+        #[parameterized(input = { 1u8, 2u16, 3u32 }, expected = { 6, 7, 8 })]
+        fn inferred<T>(input: T, expected: u32)
+        where
+            T: Into<u32>,
+        {
+            assert_eq!(add5(input), expected);
+        }
+
+        #[parameterized(bytes = { [1], [1, 2], [1, 2, 3] })]
+        fn inferred_const<const N: usize>(bytes: [u8; N]) {
+            assert_eq!(bytes.len(), bytes[N - 1] as usize);
+        }
+
+        #[parameterized(T = type { u8, u16 }, input = { 1u8, 2u32 })]
+        fn explicit_and_inferred<T: Codec, U: Into<u64> + Copy>(input: U) {
+            assert_eq!(T::decode(input.into()).encode(), input.into());
+        }
+    }
+
+    mod impl_trait {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(f = { |x| x + 1, |x| x * 2 }, expected = { 4, 6 })]
+        fn closures(f: impl Fn(u8) -> u8, expected: u8) {
+            assert_eq!(f(3), expected);
+        }
+
+        #[parameterized(values = { 0..3, vec![1, 1, 1].into_iter() }, expected = { 3, 3 })]
+        fn iterators(values: impl Iterator<Item = u32>, expected: usize) {
+            assert_eq!(values.count(), expected);
+        }
+
+        #[parameterized(values = { vec![1, 2], vec![3] }, expected = { "12", "3" })]
+        fn nested(values: Vec<impl std::fmt::Display>, expected: &str) {
+            let joined = values.iter().map(|v| v.to_string()).collect::<String>();
+            assert_eq!(joined, expected);
+        }
+
+        #[parameterized(T = type { u8, u16 }, f = { |x| x, |x| x })]
+        fn with_generics<T: From<u8> + PartialEq + std::fmt::Debug>(f: impl Fn(T) -> T) {
+            assert_eq!(f(T::from(1)), T::from(1));
+        }
+    }
+
+    mod fixtures {
+        use super::*;
+
+        ide!();
+
+        #[derive(Default)]
+        struct Counter {
+            count: u32,
+        }
+
+        impl Counter {
+            fn starting_at_ten() -> Self {
+                Self { count: 10 }
+            }
+        }
+
+        #[parameterized]
+        impl Counter {
+            #[parameterized(input = { 1, 2 }, expected = { 1, 2 })]
+            fn add(&mut self, input: u32, expected: u32) {
+                self.count += input;
+                assert_eq!(self.count, expected);
+            }
+
+            #[parameterized(input = { 1, 2 }, expected = { 11, 12 }, fixture = Counter::starting_at_ten)]
+            fn add_from_ten(&self, input: u32, expected: u32) {
+                assert_eq!(self.count + input, expected);
+            }
+
+            #[parameterized(other = { Self::default(), Self::starting_at_ten() }, expected = { 0, 10 })]
+            fn consume(self, other: Self, expected: u32) {
+                assert_eq!(self.count + other.count, expected);
+            }
+
+            #[parameterized(input = { 3 })]
+            fn associated(input: u32) {
+                assert_eq!(Self { count: input }.count, 3);
+            }
+
+            #[parameterized(input = { 10, 11 })]
+            fn indexed(&self, #[case_index] i: u32, input: u32) {
+                assert_eq!(self.count + i + 10, input);
+            }
+        }
+
+        #[derive(Default)]
+        struct Ctx;
+
+        #[parameterized(fixture = Ctx::new)]
+        impl Ctx {
+            fn new() -> Self {
+                Ctx
+            }
+
+            fn double(&self, value: u8) -> u8 {
+                value * 2
+            }
+
+            #[parameterized(input = { 1, 2 }, expected = { 2, 4 })]
+            fn check(&self, input: u8, expected: u8) {
+                assert_eq!(self.double(input), expected);
+            }
+        }
+    }
+
+    mod flat {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(input = { 1, 2 }, expected = { 2, 3 }, flat)]
+        fn add_one(input: u8, expected: u8) {
+            assert_eq!(input + 1, expected);
+        }
+
+        #[parameterized(input = { 1, 2, 3 }, sample = 2, seed = 1, flat)]
+        fn sampled(input: u8) {
+            assert!(input <= 3);
+        }
+
+        #[parameterized(input = { 1, 2 }, flat, aggregate)]
+        fn aggregated(input: u8) {
+            assert!(input > 0);
+        }
+
+        #[test]
+        fn prefixed_names() {
+            add_one_case_0();
+            add_one_case_1();
+            assert_eq!(SAMPLED_PARAMETERIZED_SEED, 1);
+            assert_eq!(AGGREGATED_CASES.len(), 2);
+        }
+
+        #[test]
+        fn within_function_body() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+
+            static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+            struct Local(u8);
+
+            // the test harness doesn't collect test cases within a function body, so they're
+            // registered in `LOCAL_CASES` and run from here instead
+            #[parameterized(value = { Local(1), Local(2), Local(3) }, flat, harness)]
+            fn local(value: Local) {
+                RUNS.fetch_add(1, Ordering::SeqCst);
+                assert!(value.0 > 0);
+            }
+
+            #[parameterized(value = { Local(1), Local(0) }, flat, harness)]
+            fn failing(value: Local) {
+                assert!(value.0 > 0);
+            }
+
+            crate::harness::aggregate(LOCAL_CASES);
+            assert_eq!(RUNS.load(Ordering::SeqCst), 3);
+
+            let payload =
+                std::panic::catch_unwind(|| crate::harness::aggregate(FAILING_CASES)).unwrap_err();
+            let summary = payload.downcast_ref::<String>().unwrap();
+            assert!(summary.starts_with("1 of 2 parameterized cases failed:\n"));
+            assert!(summary.contains("failing_case_1): value = Local(0)\n"));
+        }
+
+        // cases expanded at module level by a declarative macro are collected as usual
+        macro_rules! doubled {
+            ($name:ident: $ty:ty = { $($value:expr),* }) => {
+                #[parameterized(value = { $($value),* }, flat)]
+                fn $name(value: $ty) {
+                    assert_eq!(value * 2 / 2, value);
+                }
+            };
+        }
+
+        doubled!(doubled_u8: u8 = { 0, 1, 100 });
+        doubled!(doubled_u16: u16 = { 0, 1, 1000 });
+
+        // a sibling with the same name as the test function doesn't collide with its cases
+        #[allow(dead_code)]
+        mod add_one {}
+    }
+
+    mod module_name {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(input = { 1, 2 }, module = double_tests)]
+        pub(super) fn double(input: u8) {
+            assert_eq!(input * 2 / 2, input);
+        }
+
+        // the test function's name is still free to use
+        #[allow(dead_code)]
+        fn double() {}
+
+        #[test]
+        fn renamed_module() {
+            double_tests::case_0();
+            double_tests::case_1();
+        }
+    }
+
+    mod harness {
+        use super::*;
+        use crate::harness::{run, Arguments, ArgumentsProvider, Format, Runner};
+
+        ide!();
+
+        #[parameterized(input = { 1, u8::MAX, (2 + 3) * 4 }, values = { vec![1, 2], Vec::new(), vec![-1] }, harness)]
+        fn registered(input: u8, values: Vec<i8>) {
+            assert!(input > 1, "input was {}", input);
+            assert!(values.len() < 3);
+        }
+
+        #[parameterized(input = { 1 }, harness)]
+        #[ignore]
+        fn ignored(input: u8) {
+            assert_eq!(input, 1);
+        }
+
+        fn report(args: &[&str]) -> (bool, String) {
+            let arguments = Arguments::parse(args.iter().map(|arg| arg.to_string())).unwrap();
+            let mut out = Vec::new();
+            let success = run(&arguments, &[registered::CASES, ignored::CASES], &mut out).unwrap();
+
+            (success, String::from_utf8(out).unwrap())
+        }
+
+        #[test]
+        fn arguments_as_written() {
+            let parameters = registered::CASES
+                .iter()
+                .map(|case| case.parameters)
+                .collect::<Vec<_>>();
+
+            assert_eq!(parameters[0], &[("input", "1"), ("values", "vec![1, 2]")]);
+            assert_eq!(
+                parameters[1],
+                &[("input", "u8::MAX"), ("values", "Vec::new()")]
+            );
+            assert_eq!(
+                parameters[2],
+                &[("input", "(2 + 3) * 4"), ("values", "vec![-1]")]
+            );
+            assert_eq!(
+                registered::CASES[0].name(),
+                "tests::harness::registered::case_0"
+            );
+        }
+
+        #[test]
+        fn failures_are_reported_with_parameters() {
+            let (success, out) = report(&[]);
+
+            assert!(!success);
+            assert!(out.contains("test tests::harness::registered::case_0 ... FAILED"));
+            assert!(out.contains("test tests::harness::registered::case_1 ... ok"));
+            assert!(out.contains("test tests::harness::ignored::case_0 ... ignored"));
+            assert!(out.contains("parameters: input = 1, values = vec![1, 2]"));
+            assert!(out.contains("input was 1"));
+            assert!(out.contains("2 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out"));
+        }
+
+        #[test]
+        fn filters() {
+            let (success, out) = report(&["case_1", "--skip", "ignored"]);
+            assert!(success);
+            assert!(out.contains("1 passed; 0 failed; 0 ignored; 0 measured; 3 filtered out"));
+
+            let (success, out) = report(&["--ignored"]);
+            assert!(success);
+            assert!(out.contains("1 passed; 0 failed; 0 ignored; 0 measured; 3 filtered out"));
+
+            let (_, out) = report(&["--list", "--exact", "tests::harness::ignored::case_0"]);
+            assert_eq!(
+                out,
+                "tests::harness::ignored::case_0: test\n\n1 test, 0 benchmarks\n"
+            );
+        }
+
+        #[test]
+        fn reports() {
+            let (_, out) = report(&["--format", "json", "registered::case_1"]);
+            assert!(out.contains(r#""name": "tests::harness::registered::case_1", "event": "ok", "parameters": { "input": "u8::MAX", "values": "Vec::new()" }"#));
+
+            let (_, out) = report(&["--format=junit"]);
+            assert!(
+                out.contains(r#"<testcase classname="tests::harness::registered" name="case_0""#)
+            );
+            assert!(out.contains(r#"<property name="values" value="vec![1, 2]"/>"#));
+            assert!(out.contains("<failure type=\"assert\" message=\"panicked at"));
+            assert!(out.contains("<skipped/>"));
+
+            let arguments = Arguments::parse(vec!["-q".to_string()]).unwrap();
+            assert_eq!(arguments.format, Format::Terse);
+            assert!(Arguments::parse(vec!["--unknown".to_string()]).is_err());
+        }
+
+        struct Squares(u32);
+
+        impl ArgumentsProvider for Squares {
+            type Arguments = (u32, u32);
+            type Cases = Vec<(String, (u32, u32))>;
+
+            fn provide(&self) -> Self::Cases {
+                (0..self.0)
+                    .map(|n| (format!("square_of_{}", n), (n, n * n)))
+                    .collect()
+            }
+        }
+
+        struct Unavailable;
+
+        impl ArgumentsProvider for Unavailable {
+            type Arguments = u32;
+            type Cases = Vec<(String, u32)>;
+
+            fn provide(&self) -> Self::Cases {
+                panic!("database fixture is unavailable")
+            }
+        }
+
+        #[test]
+        fn factories() {
+            let mut out = Vec::new();
+            let success = Runner::new()
+                .factory("squares", Squares(3), |(n, square)| {
+                    assert_eq!(n * n, square)
+                })
+                .factory("odd", Squares(2), |(n, _)| {
+                    if n % 2 == 1 {
+                        Ok(())
+                    } else {
+                        Err(format!("{} is even", n))
+                    }
+                })
+                .factory("unavailable", Unavailable, |_| {})
+                .run(&Arguments::default(), &mut out)
+                .unwrap();
+            let out = String::from_utf8(out).unwrap();
+
+            assert!(!success);
+            assert!(out.contains("test squares::square_of_2 ... ok"));
+            assert!(out.contains("test odd::square_of_0 ... FAILED"));
+            assert!(out.contains("parameters: arguments = (0, 0)\nError: \"0 is even\""));
+            assert!(out.contains("test odd::square_of_1 ... ok"));
+            assert!(out.contains("test unavailable ... FAILED"));
+            assert!(out.contains("unable to provide the test cases: panicked at"));
+            assert!(out.contains("4 passed; 2 failed; 0 ignored"));
+        }
+    }
+
+    mod compile_fail {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(source = {
+            "pub const TEXT: u8 = \"text\";",
+            "pub fn unit() -> u8 { () }",
+            "tests/compile_fail/missing_field.rs",
+        }, compile_fail)]
+        fn rejected(source: &str) -> &str {
+            source
+        }
+
+        #[parameterized(body = { "1 + 1" }, compile_fail)]
+        #[should_panic(expected = "compiled successfully")]
+        fn accepted(body: &str) -> String {
+            format!("pub const TWO: u8 = {};", body)
+        }
+    }
+
+    mod bench {
+        use crate::bench::run;
+        use crate::harness::Arguments;
+        use crate::parameterized_bench;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        ide!();
+
+        #[parameterized_bench(input = { 1, u16::MAX, 7 }, where = |input| input != 7)]
+        fn square(input: u16) -> u32 {
+            u32::from(input) * u32::from(input)
+        }
+
+        #[parameterized_bench(values = { vec![3, 1, 2], Vec::new() })]
+        fn first(values: Vec<u8>, #[case_index] index: usize) -> u8 {
+            assert_eq!(index, 0, "no first value");
+            values[0]
+        }
+
+        static LIVE: AtomicUsize = AtomicUsize::new(0);
+        static MAX_LIVE: AtomicUsize = AtomicUsize::new(0);
+
+        /// Counts the inputs and outputs of a benchmark which are held at once.
+        struct Live;
+
+        impl Live {
+            fn new() -> Self {
+                let live = LIVE.fetch_add(1, Ordering::SeqCst) + 1;
+                MAX_LIVE.fetch_max(live, Ordering::SeqCst);
+                Live
+            }
+        }
+
+        impl Drop for Live {
+            fn drop(&mut self) {
+                LIVE.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        #[parameterized_bench(value = { Live::new() })]
+        fn held(value: Live) -> Live {
+            value
+        }
+
+        fn report(arguments: &Arguments) -> (bool, String) {
+            let mut out = Vec::new();
+            let success = run(arguments, &[square::BENCHES, first::BENCHES], &mut out).unwrap();
+
+            (success, String::from_utf8(out).unwrap())
+        }
+
+        #[test]
+        fn registered() {
+            assert_eq!(square::BENCHES.len(), 2);
+            assert_eq!(square::BENCHES[1].index, 1);
+            assert_eq!(square::BENCHES[1].parameters, &[("input", "u16::MAX")]);
+            assert_eq!(first::BENCHES[1].name(), "tests::bench::first::case_1");
+        }
+
+        #[test]
+        fn run_once() {
+            let (success, out) = report(&Arguments::default());
+
+            assert!(!success);
+            assert!(out.contains("running 4 benchmarks"));
+            assert!(out.contains("bench tests::bench::square::case_1 (input = u16::MAX) ... ok"));
+            assert!(
+                out.contains("bench tests::bench::first::case_1 (values = Vec::new()) ... FAILED")
+            );
+            assert!(out.contains("no first value"));
+            assert!(out.contains("bench result: FAILED. 3 passed; 1 failed"));
+        }
+
+        #[test]
+        fn measured() {
+            let arguments = Arguments {
+                bench: true,
+                filters: vec!["square::case_0".to_string()],
+                ..Arguments::default()
+            };
+            let (success, out) = report(&arguments);
+
+            assert!(success);
+            assert!(out.contains("bench tests::bench::square::case_0 (input = 1) ... mean "));
+            assert!(out.contains(" samples of "));
+        }
+
+        #[test]
+        fn batched() {
+            let arguments = Arguments {
+                bench: true,
+                ..Arguments::default()
+            };
+            let mut out = Vec::new();
+
+            assert!(run(&arguments, &[held::BENCHES], &mut out).unwrap());
+            // the inputs and outputs of a single batch are held at once, not those of a sample
+            assert!(MAX_LIVE.load(Ordering::SeqCst) <= 1024);
+            assert_eq!(LIVE.load(Ordering::SeqCst), 0);
+        }
+    }
+
+    mod aggregate {
+        use super::*;
+
+        ide!();
+
+        #[parameterized(input = { 1, 2, 3, 4 }, expected = { 2, 4, 6, 8 }, aggregate)]
+        fn doubled(input: u32, expected: u32) {
+            assert_eq!(input * 2, expected);
+        }
+
+        #[parameterized(values = { vec![], vec![1] }, aggregate)]
+        #[should_panic(expected = "out of bounds")]
+        fn each_panics(values: Vec<u8>) {
+            let _ = values[2];
+        }
+
+        // ignored, so the aggregated test doesn't fail; its summary is checked below instead
+        #[parameterized(input = { 1, 2, u16::MAX, 4 }, expected = { 1, 3, 0, 4 }, aggregate)]
+        #[ignore]
+        fn failing(input: u16, expected: u16) {
+            assert_eq!(input, expected);
+        }
+
+        #[test]
+        fn summary_of_failures() {
+            let payload =
+                std::panic::catch_unwind(|| crate::harness::aggregate(failing::CASES)).unwrap_err();
+            let summary = payload.downcast_ref::<String>().unwrap();
+
+            assert!(summary.starts_with("2 of 4 parameterized cases failed:\n"));
+            assert!(summary.contains(
+                "\ncase #1 (tests::aggregate::failing::case_1): input = 2, expected = 3\n"
+            ));
+            assert!(summary.contains(
+                "\ncase #2 (tests::aggregate::failing::case_2): input = u16::MAX, expected = 0\n"
+            ));
+            assert!(summary.contains("    assertion"));
+            assert!(!summary.contains("case #0"));
+        }
+    }
 }
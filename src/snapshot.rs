@@ -0,0 +1,178 @@
+//! Snapshots of the values returned by parameterized test cases, as used by the `snapshot`
+//! option of the `#[parameterized]` attribute.
+//!
+//! The snapshot of a test case is stored in the crate which defines it, under
+//! `tests/snapshots/<module path>/<case>.snap`, where `<module path>` is the path of the module
+//! generated for the test function, e.g. `my_crate/tests/split` for `my_crate::tests::split`, and
+//! `<case>` the name of the test case, e.g. `case_0`. With `flat`, the path of the module which
+//! contains the test function is followed by its name instead.
+//!
+//! A missing snapshot is created by the first run of its test case, and compared by later runs.
+//! The `PARAMETERIZED_UPDATE` environment variable changes this:
+//!
+//! * `PARAMETERIZED_UPDATE=1` rewrites the snapshots of the test cases which are run, instead of
+//!   comparing them;
+//! * `PARAMETERIZED_UPDATE=0` never writes a snapshot, so a test case without a snapshot fails,
+//!   e.g. on CI, where a snapshot which wasn't committed shouldn't be silently created.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The environment variable which controls whether snapshots are written: if set to `1`, the
+/// snapshots of the test cases which are run are rewritten, and if set to `0`, missing snapshots
+/// are not created.
+pub const UPDATE_VARIABLE: &str = "PARAMETERIZED_UPDATE";
+
+/// Which snapshots are written, rather than compared.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Update {
+    /// Missing snapshots are created, and existing ones compared (the default)
+    Missing,
+    /// All snapshots are rewritten (`PARAMETERIZED_UPDATE=1`)
+    All,
+    /// No snapshot is written, and a missing snapshot fails (`PARAMETERIZED_UPDATE=0`)
+    None,
+}
+
+impl Update {
+    /// The snapshots to write, as requested by the `PARAMETERIZED_UPDATE` environment variable.
+    pub(crate) fn requested() -> Self {
+        match std::env::var(UPDATE_VARIABLE).as_deref() {
+            Ok("1") => Update::All,
+            Ok("0") => Update::None,
+            _ => Update::Missing,
+        }
+    }
+}
+
+/// Compare the representation of a returned value with the snapshot of a test case, or write it
+/// if it doesn't exist yet, or snapshots are being updated.
+///
+/// # Panics
+///
+/// Panics if the snapshot differs from the given value, or can't be read or written, or if it
+/// doesn't exist while `PARAMETERIZED_UPDATE=0` is set.
+pub fn assert_snapshot(manifest_dir: &str, module_path: &str, case: &str, actual: &str) {
+    let path = snapshot_path(
+        Path::new(manifest_dir),
+        module_path,
+        &format!("{}.snap", case),
+    );
+
+    assert_matches_file(
+        &path,
+        actual,
+        "the value returned by the test function",
+        Update::requested(),
+    );
+}
+
+/// Compare `actual` with the contents of the file at `path`, or write it as requested by
+/// `update`. The `subject` describes `actual` when they differ, or when the file doesn't exist.
+pub(crate) fn assert_matches_file(path: &Path, actual: &str, subject: &str, update: Update) {
+    if update == Update::All {
+        return write_snapshot(path, actual);
+    }
+
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound && update == Update::Missing => {
+            return write_snapshot(path, actual)
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => panic!(
+            "the snapshot {} doesn't exist\n\
+                (rerun with {}=1 to create it from {})\n\n{}",
+            path.display(),
+            UPDATE_VARIABLE,
+            subject,
+            normalize(actual),
+        ),
+        Err(err) => panic!("unable to read snapshot {}: {}", path.display(), err),
+    };
+
+    let expected = normalize(&expected);
+    let actual = normalize(actual);
+
+    if expected != actual {
+        panic!(
+            "{} differs from the snapshot {}\n\
+                (rerun with {}=1 to update the snapshot)\n\n{}",
+            subject,
+            path.display(),
+            UPDATE_VARIABLE,
+            diff(&expected, &actual),
+        );
+    }
+}
+
+/// The path of the snapshot file named `file`, of a test case of the module at `module_path`,
+/// e.g. `my_crate::tests::split`.
+pub(crate) fn snapshot_path(manifest_dir: &Path, module_path: &str, file: &str) -> PathBuf {
+    manifest_dir
+        .join("tests")
+        .join("snapshots")
+        .join(module_directory(module_path))
+        .join(file)
+}
+
+/// The relative directory of the module at `module_path`, e.g. `my_crate/tests/split`.
+pub(crate) fn module_directory(module_path: &str) -> PathBuf {
+    module_path.split("::").collect()
+}
+
+fn write_snapshot(path: &Path, actual: &str) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(path, format!("{}\n", normalize(actual))));
+
+    if let Err(err) = result {
+        panic!("unable to write snapshot {}: {}", path.display(), err);
+    }
+}
+
+/// Snapshots are compared by line, regardless of line endings and the final newline.
+fn normalize(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .trim_end_matches('\n')
+        .to_string()
+}
+
+/// A line diff between the snapshot and the actual value, where removed lines of the snapshot
+/// are prefixed with `-`, and added lines of the actual value with `+`.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+
+    // lengths of the longest common subsequences of the remaining lines
+    let mut lengths = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lengths[i][j] = if expected[i] == actual[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            out.push_str(&format!(" {}\n", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j < actual.len() && (i == expected.len() || lengths[i][j + 1] > lengths[i + 1][j])
+        {
+            out.push_str(&format!("+{}\n", actual[j]));
+            j += 1;
+        } else {
+            out.push_str(&format!("-{}\n", expected[i]));
+            i += 1;
+        }
+    }
+
+    out
+}
@@ -0,0 +1,11 @@
+//! Compile-fail test cases, whose sources use the library of this crate.
+
+use parameterized::parameterized;
+
+#[parameterized(statement = {
+    "let _ = parameterized::current_case().unwrap().index;",
+    "let _: u32 = parameterized::snapshot::UPDATE_VARIABLE;",
+}, compile_fail)]
+fn misused_api(statement: &str) -> String {
+    format!("pub fn check() {{\n    {}\n}}\n", statement)
+}
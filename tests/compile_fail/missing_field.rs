@@ -0,0 +1,6 @@
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+pub const ORIGIN: Point = Point { x: 0 };
@@ -0,0 +1,64 @@
+//! A test target without the default test harness (`harness = false`), whose test cases are run
+//! by `parameterized::harness`.
+
+use parameterized::parameterized;
+
+#[parameterized(input = { 0, 1, u16::MAX }, expected = { 5, 6, 65540 }, harness)]
+fn add5(input: u16, expected: u32) {
+    assert_eq!(u32::from(input) + 5, expected);
+}
+
+#[parameterized(input = { "1", "2" }, harness)]
+fn parse(input: &str) -> Result<(), std::num::ParseIntError> {
+    input.parse::<u8>().map(|_| ())
+}
+
+#[parameterized(values = { vec![], vec![1] }, index = { 0, 1 }, harness)]
+#[should_panic(expected = "out of bounds")]
+fn index(values: Vec<u8>, index: usize) {
+    let _ = values[index];
+}
+
+#[parameterized(input = { 1, 2 }, harness)]
+#[ignore]
+fn slow(input: u8) {
+    assert!(input > 0);
+}
+
+/// Provides a test case for each source file of this crate, which can only be found at runtime.
+struct SourceFiles;
+
+impl parameterized::harness::ArgumentsProvider for SourceFiles {
+    type Arguments = std::path::PathBuf;
+    type Cases = Vec<(String, std::path::PathBuf)>;
+
+    fn provide(&self) -> Self::Cases {
+        let directory = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+
+        std::fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .map(|path| {
+                (
+                    path.file_stem().unwrap().to_string_lossy().into_owned(),
+                    path,
+                )
+            })
+            .collect()
+    }
+}
+
+fn not_empty(path: std::path::PathBuf) -> std::io::Result<()> {
+    assert!(std::fs::metadata(path)?.len() > 0);
+    Ok(())
+}
+
+fn main() {
+    parameterized::harness::Runner::new()
+        .cases(add5::CASES)
+        .cases(parse::CASES)
+        .cases(index::CASES)
+        .cases(slow::CASES)
+        .factory("source_files", SourceFiles, not_empty)
+        .main();
+}